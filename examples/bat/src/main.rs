@@ -3,40 +3,40 @@ use std::{io, fmt};
 use std::path::PathBuf;
 
 use yansi::{Style, Color::*, Attribute::*, Quirk::*};
-use jellybean::{Language, Highlight, Theme};
+use jellybean::{Language, Highlight, Theme, CompiledTheme, Capture};
 
 // This is just an arbitrary theme.
 pub static THEME: Theme<Style> = Theme::new(&[
-    ("attribute", Blue.foreground()),
-    ("comment", BrightBlack.foreground()),
-    ("constant", Red.bright()),
-    ("constructor", Blue.foreground()),
-    ("escape", BrightRed.foreground()),
-    ("function", Blue.bright()),
-    ("function.builtin", Magenta.foreground()),
-    ("function.macro", Magenta.foreground()),
-    ("keyword", Red.foreground()),
-    ("label", Magenta.foreground().dim()),
-    ("none", Clear.style()),
-    ("operator", Magenta.bold()),
-    ("property", Cyan.foreground()),
-    ("punctuation", Bold.style()),
-    ("punctuation.bracket", Primary.bold()),
-    ("punctuation.delimiter", Primary.bold()),
-    ("punctuation.special", Magenta.bold()),
-    ("string", Green.bright()),
-    ("string.special", Green.bright()),
-    ("tag", BrightRed.foreground()),
-    ("text", Primary.foreground()),
-    ("text.reference", Blue.foreground()),
-    ("text.strike", Strike.style()),
-    ("text.title", Magenta.foreground()),
-    ("text.uri", Green.underline()),
-    ("type", Blue.foreground()),
-    ("type.builtin", Yellow.foreground()),
-    ("variable", Cyan.foreground()),
-    ("variable.builtin", Yellow.foreground()),
-    ("variable.parameter", Red.foreground()),
+    (Capture::Attribute, Blue.foreground()),
+    (Capture::Comment, BrightBlack.foreground()),
+    (Capture::Constant, Red.bright()),
+    (Capture::Constructor, Blue.foreground()),
+    (Capture::Escape, BrightRed.foreground()),
+    (Capture::Function, Blue.bright()),
+    (Capture::FunctionBuiltin, Magenta.foreground()),
+    (Capture::FunctionMacro, Magenta.foreground()),
+    (Capture::Keyword, Red.foreground()),
+    (Capture::Label, Magenta.foreground().dim()),
+    (Capture::None, Clear.style()),
+    (Capture::Operator, Magenta.bold()),
+    (Capture::Property, Cyan.foreground()),
+    (Capture::Punctuation, Bold.style()),
+    (Capture::PunctuationBracket, Primary.bold()),
+    (Capture::PunctuationDelimiter, Primary.bold()),
+    (Capture::PunctuationSpecial, Magenta.bold()),
+    (Capture::String, Green.bright()),
+    (Capture::StringSpecial, Green.bright()),
+    (Capture::Tag, BrightRed.foreground()),
+    (Capture::Text, Primary.foreground()),
+    (Capture::TextReference, Blue.foreground()),
+    (Capture::TextStrike, Strike.style()),
+    (Capture::TextTitle, Magenta.foreground()),
+    (Capture::TextUri, Green.underline()),
+    (Capture::Type, Blue.foreground()),
+    (Capture::TypeBuiltin, Yellow.foreground()),
+    (Capture::Variable, Cyan.foreground()),
+    (Capture::VariableBuiltin, Yellow.foreground()),
+    (Capture::VariableParameter, Red.foreground()),
 ]);
 
 fn parse_cli_args() -> PathBuf {
@@ -63,13 +63,14 @@ fn parse_cli_args() -> PathBuf {
 fn write_styled_event(
     out: &mut dyn fmt::Write,
     stack: &mut Vec<Style>,
+    theme: &CompiledTheme<'_, Style>,
     highlight: Highlight<'_>
 ) -> fmt::Result {
     static DEFUALT: Style = Style::new();
 
     match highlight {
-        Highlight::Start { group, .. } => {
-            let style = THEME.find(group).unwrap_or(&DEFUALT);
+        Highlight::Start { index, .. } => {
+            let style = theme.get(index).unwrap_or(&DEFUALT);
             stack.push(*style);
             style.fmt_prefix(out)
         },
@@ -88,25 +89,24 @@ fn main() -> io::Result<()> {
     let input = parse_cli_args();
     let source = std::fs::read_to_string(&input)?;
 
-    // Use the input's extension, if any, to find the associated language.
-    let ext = input.extension().map(|ext| ext.to_string_lossy());
-    let language = ext.as_ref()
-        .and_then(|ext| Language::find(ext))
-        .or_else(|| input.file_name().and_then(|f| f.to_str()).and_then(Language::find));
+    // Detect the language from the input's path and contents.
+    let language = Language::detect(&input, &source);
 
     // Print the source with terminal colors if we have a language highlighter.
     let mut stack = vec![];
     let output = if let Some(language) = language {
         let mut output = String::with_capacity(source.len());
-        language.highlighter()
-            .highlight(&source)
-            .try_for_each(|event| write_styled_event(&mut output, &mut stack, event.unwrap()))
+        let mut highlighter = language.highlighter().expect("precached highlighter");
+        let theme = THEME.compile(&highlighter);
+        highlighter.highlight(&source)
+            .try_for_each(|event| {
+                write_styled_event(&mut output, &mut stack, &theme, event.unwrap())
+            })
             .expect("foo bar");
 
         output
     } else {
-        let ext = ext.unwrap_or("[empty]".into());
-        eprintln!("warning: emitting plaintext ({:?} not recognized)", ext);
+        eprintln!("warning: emitting plaintext ({:?} not recognized)", input);
         source
     };
 
@@ -26,8 +26,7 @@ fn run_split() {
     let start = std::time::Instant::now();
     let serializable_highlighters = ALL_LANGUAGES.par_iter()
         .filter(|lang| !SLOW_LANGUAGES.contains(&lang.name()))
-        .map(|language| language.custom_highlighter(COMMON_CAPTURES))
-        .map(|highlighter| highlighter.serializable())
+        .map(|language| language.custom_highlighter(COMMON_CAPTURES)?.serializable())
         .collect::<Result<Vec<_>, _>>()
         .unwrap();
 
@@ -56,8 +55,7 @@ fn run_big_blob() {
     let start = std::time::Instant::now();
     let serializable_highlighters = ALL_LANGUAGES.par_iter()
         .filter(|lang| !SLOW_LANGUAGES.contains(&lang.name()))
-        .map(|language| language.custom_highlighter(COMMON_CAPTURES))
-        .map(|highlighter| highlighter.serializable())
+        .map(|language| language.custom_highlighter(COMMON_CAPTURES)?.serializable())
         .collect::<Result<Vec<_>, _>>()
         .unwrap();
 
@@ -77,7 +75,7 @@ fn run_big_blob() {
 
 fn run_per_language(top: usize) {
     let mut results: Vec<(_, _, u128)> = ALL_LANGUAGES.iter()
-        .map(|lang| (lang, lang.highlight_config(COMMON_CAPTURES)))
+        .map(|lang| (lang, lang.highlight_config(COMMON_CAPTURES).unwrap()))
         .map(|(lang, hl)| (lang, hl.serializable().unwrap()))
         .map(|(lang, data)| (lang, bincode::serialize(&data).unwrap()))
         .map(|(lang, bytes)| {
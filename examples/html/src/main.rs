@@ -109,7 +109,8 @@ fn main() -> std::io::Result<()> {
     // Generate the HTML, using the language's highlighter.
     let mut html = html_prefix(&source);
     if let Some(language) = language {
-        for event in language.highlighter().highlight(&source) {
+        let mut highlighter = language.highlighter().expect("precached highlighter");
+        for event in highlighter.highlight(&source) {
             html_line_styled(&mut html, event.unwrap());
         }
     } else {
@@ -3,7 +3,12 @@ use std::fs::File;
 use std::path::PathBuf;
 use std::io::{self, BufWriter, Write};
 
-include!("src/capture.rs");
+#[allow(dead_code)]
+mod capture {
+    include!("src/capture.rs");
+}
+
+use capture::*;
 
 // Written by `xtask sync`.
 const PACKS: &[PackMetdata] = include!("metadata.rs");
@@ -33,13 +38,17 @@ mod precached {
                 .map(|(_, v)| *v)
         }
 
+        fn normalized_query(&self, name: &str) -> std::borrow::Cow<'static, str> {
+            normalize_query(self.query(name).unwrap_or(""), normalize_capture)
+        }
+
         pub fn highlight_config(&self) -> HighlightConfiguration {
             let config = HighlightConfiguration::new(
                 (self.language)(),
                 self.name,
-                self.query("highlights").unwrap_or(""),
-                self.query("injections").unwrap_or(""),
-                self.query("locals").unwrap_or(""),
+                &self.normalized_query("highlights"),
+                &self.normalized_query("injections"),
+                &self.normalized_query("locals"),
                 true,
             );
 
@@ -78,6 +87,14 @@ fn crate_feature_active(feat: &str) -> bool {
     env::var_os(format!("CARGO_FEATURE_{}", feat.to_uppercase())).is_some()
 }
 
+/// Returns the `Capture` variant for `capture`: its dotted parts, capitalized
+/// and concatenated, as in `FunctionMacro` for `function.macro`.
+fn variant_name(capture: &str) -> String {
+    capture.split('.')
+        .map(|part| part[..1].to_uppercase() + &part[1..])
+        .collect()
+}
+
 fn main() -> io::Result<()> {
     println!("cargo:rerun-if-changed=metadata.rs");
     let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
@@ -120,5 +137,18 @@ fn main() -> io::Result<()> {
     writeln!(&mut sink, "    }}")?;
     writeln!(&mut sink, "}}")?;
 
+    // Generate the standard captures macro.
+    writeln!(&mut sink, "#[doc(hidden)]")?;
+    writeln!(&mut sink, "#[macro_export]")?;
+    writeln!(&mut sink, "macro_rules! with_standard_captures {{")?;
+    writeln!(&mut sink, "    ($m:ident) => {{")?;
+    writeln!(&mut sink, "        $m! {{")?;
+    for capture in STANDARD_CAPTURES {
+        writeln!(&mut sink, "            {} => {capture:?},", variant_name(capture))?;
+    }
+    writeln!(&mut sink, "        }}")?;
+    writeln!(&mut sink, "    }}")?;
+    writeln!(&mut sink, "}}")?;
+
     Ok(())
 }
@@ -0,0 +1,78 @@
+use crate::Language;
+use crate::util::cmp_ignore_case_ascii;
+
+/// Alternative names for languages, as written in Markdown code fences and by
+/// users, keyed by language name. Adapted from GitHub Linguist's aliases.
+///
+/// No alias is the name or another alias of a different language.
+static ALIASES: &[(&str, &[&str])] = &[
+    ("bash", &["sh", "shell", "shell-script", "zsh", "ksh"]),
+    ("c_sharp", &["c#", "csharp", "cs"]),
+    ("clojure", &["clj", "cljs"]),
+    ("cpp", &["c++", "cplusplus"]),
+    ("devicetree", &["dts"]),
+    ("dockerfile", &["docker", "containerfile"]),
+    ("elisp", &["emacs-lisp"]),
+    ("embedded_template", &["erb", "ejs", "rhtml", "html+erb"]),
+    ("git_config", &["gitconfig", "git-config"]),
+    ("git_rebase", &["gitrebase", "git-rebase"]),
+    ("gitcommit", &["git-commit"]),
+    ("go", &["golang"]),
+    ("gomod", &["go.mod", "go-mod"]),
+    ("gosum", &["go.sum", "go-sum"]),
+    ("gowork", &["go.work", "go-work"]),
+    ("haskell", &["hs"]),
+    ("html", &["xhtml"]),
+    ("htmldjango", &["django", "jinja", "jinja2", "html+django"]),
+    ("janet_simple", &["janet"]),
+    ("javascript", &["js", "jsx", "node", "ecmascript"]),
+    ("json", &["jsonc", "geojson"]),
+    ("kotlin", &["kt"]),
+    ("llvm", &["llvm-ir"]),
+    ("make", &["makefile", "mf", "bsdmake"]),
+    ("markdown", &["md", "pandoc"]),
+    ("objc", &["objective-c", "objectivec", "obj-c"]),
+    ("proto", &["protobuf", "proto3"]),
+    ("python", &["py", "python3", "py3"]),
+    ("qmljs", &["qml"]),
+    ("r", &["rscript"]),
+    ("racket", &["rkt"]),
+    ("regex", &["regexp"]),
+    ("ruby", &["rb", "jruby"]),
+    ("rust", &["rs"]),
+    ("solidity", &["sol"]),
+    ("ssh_client_config", &["ssh-config", "ssh_config"]),
+    ("starlark", &["bazel", "bzl"]),
+    ("tlaplus", &["tla", "tla+"]),
+    ("typescript", &["ts"]),
+    ("vim", &["vimscript", "viml"]),
+    ("xml", &["rss", "xsd", "wsdl"]),
+];
+
+impl Language {
+    /// Returns the curated aliases of this language, such as `js` for
+    /// JavaScript or `c++` for C++, as used in Markdown code fences.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jellybean::Language;
+    ///
+    /// assert!(Language::cpp.aliases().contains(&"c++"));
+    /// assert_eq!(Language::find("C++").unwrap().name(), "cpp");
+    /// assert_eq!(Language::find("golang").unwrap().name(), "go");
+    /// ```
+    pub fn aliases(&self) -> &'static [&'static str] {
+        ALIASES.iter()
+            .find(|(name, _)| *name == self.name)
+            .map_or(&[], |(_, aliases)| aliases)
+    }
+
+    /// Finds the language with the alias `alias`, ignoring case. See
+    /// [`Language::aliases()`].
+    pub fn find_by_alias(alias: &str) -> Option<&'static Language> {
+        ALIASES.iter()
+            .find(|(_, aliases)| aliases.iter().any(|a| cmp_ignore_case_ascii(a, alias).is_eq()))
+            .and_then(|(name, _)| Language::find_by_name(name))
+    }
+}
@@ -259,3 +259,328 @@ pub static COMMON_CAPTURES: &[&str; 59] = &[
     "variable.builtin",
     "variable.parameter",
 ];
+
+/// The standard capture taxonomy, sorted by name.
+///
+/// When a highlighter is configured, every capture in a language's queries is
+/// normalized onto one of these names via [`normalize_capture()`], so a theme
+/// that styles the names in this list, or just their first components (e.g,
+/// `keyword` and `string`), styles every bundled language. The taxonomy is a
+/// superset of [`COMMON_CAPTURES`], and `none` marks text that is deliberately
+/// left unstyled. [`Capture`](crate::Capture) is generated from this list.
+pub static STANDARD_CAPTURES: &[&str; 81] = &[
+    "attribute",
+    "boolean",
+    "character",
+    "character.special",
+    "comment",
+    "comment.documentation",
+    "conditional",
+    "constant",
+    "constant.builtin",
+    "constant.builtin.boolean",
+    "constant.builtin.string",
+    "constant.macro",
+    "constant.numeric.integer",
+    "constructor",
+    "embedded",
+    "error",
+    "escape",
+    "exception",
+    "field",
+    "float",
+    "function",
+    "function.builtin",
+    "function.call",
+    "function.macro",
+    "function.method",
+    "include",
+    "keyword",
+    "keyword.coroutine",
+    "keyword.directive",
+    "keyword.function",
+    "keyword.operator",
+    "keyword.return",
+    "keyword.type",
+    "label",
+    "method",
+    "method.call",
+    "module",
+    "namespace",
+    "none",
+    "number",
+    "operator",
+    "parameter",
+    "preproc",
+    "property",
+    "punctuation",
+    "punctuation.bracket",
+    "punctuation.delimiter",
+    "punctuation.special",
+    "repeat",
+    "spell",
+    "storageclass",
+    "string",
+    "string.documentation",
+    "string.escape",
+    "string.regex",
+    "string.special",
+    "string.special.path",
+    "string.special.symbol",
+    "string.special.uri",
+    "tag",
+    "tag.attribute",
+    "tag.delimiter",
+    "text",
+    "text.danger",
+    "text.emphasis",
+    "text.literal",
+    "text.note",
+    "text.reference",
+    "text.strike",
+    "text.strong",
+    "text.title",
+    "text.underline",
+    "text.uri",
+    "text.warning",
+    "type",
+    "type.builtin",
+    "type.definition",
+    "type.qualifier",
+    "variable",
+    "variable.builtin",
+    "variable.parameter",
+];
+
+/// Pairs of `(capture, standard capture)` mapping grammar-specific captures,
+/// or their prefixes, onto the [`STANDARD_CAPTURES`], sorted by capture.
+pub static CAPTURE_ALIASES: &[(&str, &str); 67] = &[
+    ("annotation", "attribute"),
+    ("bracket", "punctuation.bracket"),
+    ("cdata", "string.special"),
+    ("char.elm", "character"),
+    ("character.escape.elm", "string.escape"),
+    ("clean", "none"),
+    ("comment.doc", "comment.documentation"),
+    ("conceal", "none"),
+    ("constant.boolean", "boolean"),
+    ("constant.character", "character"),
+    ("constant.character.escape", "string.escape"),
+    ("constant.language", "constant.builtin"),
+    ("constant.numeric.elm", "number"),
+    ("constant.numeric.float", "float"),
+    ("define", "preproc"),
+    ("definition.package", "module"),
+    ("delimiter", "punctuation.delimiter"),
+    ("function.reference", "function.call"),
+    ("function.special", "function.macro"),
+    ("head-var", "variable"),
+    ("identifier", "variable"),
+    ("identifier.constant", "constant"),
+    ("identifier.function", "function"),
+    ("identifier.method", "function.method"),
+    ("identifier.property", "property"),
+    ("identifier.type", "type"),
+    ("identifier.type.core", "type.builtin"),
+    ("interface", "type"),
+    ("invalid", "error"),
+    ("keyword.control.conditional", "conditional"),
+    ("keyword.control.function", "keyword.function"),
+    ("keyword.control.import", "include"),
+    ("keyword.control.repeat", "repeat"),
+    ("keyword.control.return", "keyword.return"),
+    ("keyword.modifier", "storageclass"),
+    ("keyword.repeat", "repeat"),
+    ("keyword.self", "variable.builtin"),
+    ("keyword.storage", "storageclass"),
+    ("keyword.storage.type", "keyword.type"),
+    ("local.function.elm", "function"),
+    ("markup", "text"),
+    ("markup.heading", "text.title"),
+    ("markup.link", "text.reference"),
+    ("markup.raw", "text.literal"),
+    ("meta.import.elm", "include"),
+    ("output-var", "variable"),
+    ("processing", "preproc"),
+    ("punctuation.section.braces", "punctuation.bracket"),
+    ("punctuation.separator.comma.elm", "punctuation.delimiter"),
+    ("source.glsl", "embedded"),
+    ("storage.type.elm", "keyword.type"),
+    ("string.special.regex", "string.regex"),
+    ("structure", "type"),
+    ("symbol", "string.special.symbol"),
+    ("tag.error", "error"),
+    ("ui.text", "text"),
+    ("union.elm", "type"),
+    ("value.boolean", "boolean"),
+    ("value.entity", "constant"),
+    ("value.null", "constant.builtin"),
+    ("value.number", "number"),
+    ("value.symbol", "string.special.symbol"),
+    ("value.symbols", "string.special.symbol"),
+    ("var.reference", "variable"),
+    ("variable.other.member", "field"),
+    ("variable.system", "variable.builtin"),
+    ("warning", "text.warning"),
+];
+
+/// Returns the standard capture `capture` is normalized to, or `None` if
+/// `capture` isn't a highlight in the taxonomy, e.g, `injection.content`.
+///
+/// The capture, then each of its dotted prefixes from longest to shortest, is
+/// looked up in [`CAPTURE_ALIASES`] and [`STANDARD_CAPTURES`]. The first hit
+/// determines the standard capture.
+///
+/// # Example
+///
+/// ```rust
+/// use jellybean::normalize_capture;
+///
+/// assert_eq!(normalize_capture("keyword.return"), Some("keyword.return"));
+/// assert_eq!(normalize_capture("value.null"), Some("constant.builtin"));
+/// assert_eq!(normalize_capture("identifier.type.struct"), Some("type"));
+/// assert_eq!(normalize_capture("keyword.control.elm"), Some("keyword"));
+/// assert_eq!(normalize_capture("injection.content"), None);
+/// ```
+pub fn normalize_capture(capture: &str) -> Option<&'static str> {
+    normalize_capture_with(capture, |_| None)
+}
+
+/// Like [`normalize_capture()`], but consults `overrides` first at each prefix.
+pub(crate) fn normalize_capture_with<'a>(
+    capture: &str,
+    overrides: impl Fn(&str) -> Option<&'a str>,
+) -> Option<&'a str> {
+    let mut candidate = capture;
+    loop {
+        if let Some(name) = overrides(candidate) {
+            return Some(name);
+        }
+
+        if let Ok(i) = CAPTURE_ALIASES.binary_search_by_key(&candidate, |(k, _)| k) {
+            return Some(CAPTURE_ALIASES[i].1);
+        }
+
+        if let Ok(i) = STANDARD_CAPTURES.binary_search(&candidate) {
+            return Some(STANDARD_CAPTURES[i]);
+        }
+
+        candidate = &candidate[..candidate.rfind('.')?];
+    }
+}
+
+/// Rewrites the captures in `query` to the names returned by `normalize`.
+///
+/// Captures for which `normalize` returns `None` are left as they are, as is
+/// everything in strings and comments. Distinct captures in one pattern that
+/// would share a name are kept distinct, so that predicates comparing them
+/// still do: later ones get a numeric suffix, as in `@keyword.1`, which is
+/// highlighted like `@keyword`.
+pub(crate) fn normalize_query<'q, 'n>(
+    query: &'q str,
+    normalize: impl FnMut(&str) -> Option<&'n str>,
+) -> std::borrow::Cow<'q, str> {
+    normalize_query_with_rewrites(query, normalize).0
+}
+
+/// Like [`normalize_query()`], but also returns each rewritten capture name
+/// as its range in `query` and the length of its replacement, in order.
+pub(crate) fn normalize_query_with_rewrites<'q, 'n>(
+    query: &'q str,
+    mut normalize: impl FnMut(&str) -> Option<&'n str>,
+) -> (std::borrow::Cow<'q, str>, Vec<(std::ops::Range<usize>, usize)>) {
+    fn is_capture_char(c: char) -> bool {
+        c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | '?' | '!')
+    }
+
+    let (mut normalized, mut copied, mut rewrites) = (String::new(), 0, vec![]);
+    // The nesting depth and the `(capture, name)` pairs of the current pattern.
+    let (mut depth, mut names) = (0usize, Vec::<(&str, String)>::new());
+    let mut chars = query.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            ';' => while chars.next_if(|(_, c)| *c != '\n').is_some() { },
+            '"' => {
+                if depth == 0 {
+                    names.clear();
+                }
+
+                while let Some((_, c)) = chars.next() {
+                    match c {
+                        '\\' => { chars.next(); }
+                        '"' => break,
+                        _ => { }
+                    }
+                }
+            }
+            '(' | '[' => {
+                if depth == 0 {
+                    names.clear();
+                }
+
+                depth += 1;
+            }
+            ')' | ']' => depth = depth.saturating_sub(1),
+            '@' => {
+                let (start, mut end) = (i + 1, i + 1);
+                while let Some((j, c)) = chars.next_if(|(_, c)| is_capture_char(*c)) {
+                    end = j + c.len_utf8();
+                }
+
+                let capture = &query[start..end];
+                let name = match names.iter().find(|(c, _)| *c == capture) {
+                    Some((_, name)) => name.clone(),
+                    None => {
+                        let standard = normalize(capture).unwrap_or(capture);
+                        let taken = |name: &str| names.iter().any(|(_, n)| n == name);
+                        let (mut name, mut k) = (standard.to_string(), 0);
+                        while taken(&name) {
+                            k += 1;
+                            name = format!("{standard}.{k}");
+                        }
+
+                        names.push((capture, name.clone()));
+                        name
+                    }
+                };
+
+                if name != capture {
+                    normalized.push_str(&query[copied..start]);
+                    normalized.push_str(&name);
+                    rewrites.push((start..end, name.len()));
+                    copied = end;
+                }
+            }
+            _ => { }
+        }
+    }
+
+    if copied == 0 {
+        return (std::borrow::Cow::Borrowed(query), rewrites);
+    }
+
+    normalized.push_str(&query[copied..]);
+    (std::borrow::Cow::Owned(normalized), rewrites)
+}
+
+/// Maps `offset` in a query normalized with `rewrites` back to the offset in
+/// the original query. Offsets within a rewritten name are clamped to it.
+pub(crate) fn original_offset(rewrites: &[(std::ops::Range<usize>, usize)], offset: usize) -> usize {
+    // The ends of the last rewrite before `offset` in the original and in
+    // the normalized query.
+    let (mut original, mut normalized) = (0, 0);
+    for (range, len) in rewrites {
+        let start = normalized + (range.start - original);
+        if offset < start {
+            break;
+        }
+
+        if offset < start + len {
+            return range.start + (offset - start).min(range.len());
+        }
+
+        (original, normalized) = (range.end, start + len);
+    }
+
+    original + (offset - normalized)
+}
@@ -0,0 +1,185 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use tree_sitter_highlight::HighlightConfiguration;
+
+use crate::{Language, Highlighter, Error};
+use crate::capture::normalize_capture_with;
+
+/// Builds a highlight configuration or highlighter for a language with
+/// queries replaced or extended at runtime.
+///
+/// Created via [`Language::config_builder()`], which starts with the
+/// language's bundled `highlights`, `injections`, and `locals` queries.
+/// Captures in the queries, bundled or not, are normalized as they are by
+/// [`Language::highlight_config()`] unless
+/// [overridden](ConfigBuilder::capture_overrides()). Errors in text appended
+/// to a bundled query are positioned relative to the start of the appended
+/// text.
+///
+/// The builder only affects the language it's created from: injected
+/// languages are highlighted with their bundled queries. To highlight an
+/// injection with custom queries, pass a configuration built by another
+/// builder to [`Injection::Config`](crate::Injection::Config).
+///
+/// # Example
+///
+/// ```rust
+/// use jellybean::{Language, Error, COMMON_CAPTURES};
+///
+/// let highlighter = Language::rust.config_builder()
+///     .append_highlights(r#"((identifier) @constant (#eq? @constant "PI"))"#)
+///     .highlighter(COMMON_CAPTURES)
+///     .unwrap();
+///
+/// let error = Language::rust.config_builder()
+///     .highlights("(identifier @oops")
+///     .highlighter(COMMON_CAPTURES)
+///     .unwrap_err();
+///
+/// assert!(matches!(error, Error::Query { query: "highlights", .. }));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConfigBuilder {
+    language: &'static Language,
+    highlights: QueryText,
+    injections: QueryText,
+    locals: QueryText,
+    overrides: HashMap<String, String>,
+}
+
+/// The text of a query under construction.
+#[derive(Debug, Clone)]
+struct QueryText {
+    text: Cow<'static, str>,
+    /// Whether `text` starts with the language's bundled query.
+    bundled: bool,
+    /// Where text appended to the bundled query starts, if any was.
+    appended: Option<usize>,
+}
+
+impl ConfigBuilder {
+    pub(crate) fn new(language: &'static Language) -> Self {
+        let query = |name| QueryText {
+            text: Cow::Borrowed(language.find_query(name).unwrap_or("")),
+            bundled: true,
+            appended: None,
+        };
+
+        ConfigBuilder {
+            language,
+            highlights: query("highlights"),
+            injections: query("injections"),
+            locals: query("locals"),
+            overrides: HashMap::new(),
+        }
+    }
+
+    /// Replaces the `highlights` query with `query`.
+    pub fn highlights(mut self, query: impl Into<Cow<'static, str>>) -> Self {
+        self.highlights = QueryText::new(query.into());
+        self
+    }
+
+    /// Replaces the `injections` query with `query`.
+    pub fn injections(mut self, query: impl Into<Cow<'static, str>>) -> Self {
+        self.injections = QueryText::new(query.into());
+        self
+    }
+
+    /// Replaces the `locals` query with `query`.
+    pub fn locals(mut self, query: impl Into<Cow<'static, str>>) -> Self {
+        self.locals = QueryText::new(query.into());
+        self
+    }
+
+    /// Appends `query` to the `highlights` query. Earlier patterns take
+    /// precedence, so appended ones only highlight otherwise unhighlighted nodes.
+    pub fn append_highlights(mut self, query: &str) -> Self {
+        self.highlights.append(query);
+        self
+    }
+
+    /// Appends `query` to the `injections` query.
+    pub fn append_injections(mut self, query: &str) -> Self {
+        self.injections.append(query);
+        self
+    }
+
+    /// Appends `query` to the `locals` query.
+    pub fn append_locals(mut self, query: &str) -> Self {
+        self.locals.append(query);
+        self
+    }
+
+    /// Replaces the capture overrides: each `(capture, name)` normalizes
+    /// `capture`, and captures it's a dotted prefix of, to `name`. Mapping a
+    /// capture to itself opts it out of normalization.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jellybean::{Language, COMMON_CAPTURES};
+    ///
+    /// let builder = Language::rust.config_builder();
+    /// assert_eq!(builder.normalize_capture("keyword.storage"), Some("storageclass"));
+    ///
+    /// let builder = builder.capture_overrides([("keyword.storage", "keyword")]);
+    /// assert_eq!(builder.normalize_capture("keyword.storage.modifier"), Some("keyword"));
+    ///
+    /// let highlighter = builder.highlighter(COMMON_CAPTURES).unwrap();
+    /// ```
+    pub fn capture_overrides<I, K, V>(mut self, overrides: I) -> Self
+        where I: IntoIterator<Item = (K, V)>, K: Into<String>, V: Into<String>
+    {
+        self.overrides = overrides.into_iter()
+            .map(|(k, v)| (k.into(), v.into()))
+            .collect();
+
+        self
+    }
+
+    /// Returns the name `capture` is normalized to in the queries or `None`
+    /// if `capture` is left as it is.
+    ///
+    /// This is [`normalize_capture()`](crate::normalize_capture()) with the
+    /// [overrides](ConfigBuilder::capture_overrides()), if any, applied.
+    pub fn normalize_capture(&self, capture: &str) -> Option<&str> {
+        normalize_capture_with(capture, |c| self.overrides.get(c).map(|s| s.as_str()))
+    }
+
+    /// Builds a highlight configuration recognizing `captures`.
+    pub fn build(&self, captures: &[impl AsRef<str>]) -> Result<HighlightConfiguration, Error> {
+        let queries = [
+            ("injections", &*self.injections.text, self.injections.appended),
+            ("locals", &*self.locals.text, self.locals.appended),
+            ("highlights", &*self.highlights.text, self.highlights.appended),
+        ];
+
+        self.language.configure_queries(queries, &self.overrides, captures)
+    }
+
+    /// Builds a highlighter recognizing `captures`.
+    pub fn highlighter(&self, captures: &'static [&'static str]) -> Result<Highlighter, Error> {
+        Ok(Highlighter::new(self.language, self.build(captures)?, captures))
+    }
+}
+
+impl QueryText {
+    fn new(text: Cow<'static, str>) -> Self {
+        QueryText { text, bundled: false, appended: None }
+    }
+
+    fn append(&mut self, extra: &str) {
+        let text = self.text.to_mut();
+        if !text.is_empty() && !text.ends_with('\n') {
+            text.push('\n');
+        }
+
+        if self.bundled && self.appended.is_none() {
+            self.appended = Some(text.len());
+        }
+
+        text.push_str(extra);
+    }
+}
@@ -0,0 +1,311 @@
+use std::path::Path;
+
+use uncased::AsUncased;
+
+use crate::{Language, ALL_LANGUAGES};
+use crate::util::cmp_ignore_case_ascii;
+
+/// The number of lines at the start and end of a file searched for a Vim
+/// modeline, as in Vim's default `modelines` setting.
+const MODELINE_LINES: usize = 5;
+
+/// The maximum number of bytes of a source parsed to rank candidates.
+pub(crate) const TRIAL_PARSE_BYTES: usize = 64 * 1024;
+
+/// Well-known file names mapped to the name of the language they're written
+/// in. Names are matched exactly, with case.
+static FILE_NAMES: &[(&str, &str)] = &[
+    (".bash_aliases", "bash"),
+    (".bash_logout", "bash"),
+    (".bash_profile", "bash"),
+    (".bashrc", "bash"),
+    (".emacs", "elisp"),
+    (".gitattributes", "gitattributes"),
+    (".gitconfig", "git_config"),
+    (".gvimrc", "vim"),
+    (".profile", "bash"),
+    (".vimrc", "vim"),
+    (".zprofile", "bash"),
+    (".zshenv", "bash"),
+    (".zshrc", "bash"),
+    ("APKBUILD", "bash"),
+    ("BUCK", "starlark"),
+    ("BUILD", "starlark"),
+    ("COMMIT_EDITMSG", "gitcommit"),
+    ("Containerfile", "dockerfile"),
+    ("Dockerfile", "dockerfile"),
+    ("GNUmakefile", "make"),
+    ("Gemfile", "ruby"),
+    ("Guardfile", "ruby"),
+    ("Makefile", "make"),
+    ("MERGE_MSG", "gitcommit"),
+    ("PKGBUILD", "bash"),
+    ("Podfile", "ruby"),
+    ("Rakefile", "ruby"),
+    ("Tiltfile", "starlark"),
+    ("Vagrantfile", "ruby"),
+    ("WORKSPACE", "starlark"),
+    ("_vimrc", "vim"),
+    ("bashrc", "bash"),
+    ("build.ninja", "ninja"),
+    ("git-rebase-todo", "git_rebase"),
+    ("go.mod", "gomod"),
+    ("go.sum", "gosum"),
+    ("go.work", "gowork"),
+    ("makefile", "make"),
+    ("meson.build", "meson"),
+    ("meson_options.txt", "meson"),
+    ("ssh_config", "ssh_client_config"),
+];
+
+/// Well-known file names that are also recognized followed by a `.` and any
+/// suffix, as in `Dockerfile.dev` or `Makefile.am`.
+static FILE_NAME_PREFIXES: &[&str] = &["Containerfile", "Dockerfile", "Makefile"];
+
+/// Multi-part extensions mapped to the name of the language they're written
+/// in, where the last part alone is either unknown or misleading.
+static COMPOUND_EXTENSIONS: &[(&str, &str)] = &[
+    ("blade.php", "php"),
+    ("d.cts", "typescript"),
+    ("d.mts", "typescript"),
+    ("d.ts", "typescript"),
+    ("html.eex", "eex"),
+    ("html.erb", "embedded_template"),
+    ("html.heex", "heex"),
+    ("html.jinja", "htmldjango"),
+    ("html.leex", "eex"),
+    ("html.twig", "twig"),
+    ("js.erb", "embedded_template"),
+];
+
+/// Interpreters in shebangs and file types in modelines mapped to the name of
+/// the language they denote, where the two differ and aren't an alias.
+static INTERPRETERS: &[(&str, &str)] = &[
+    ("ash", "bash"),
+    ("bun", "javascript"),
+    ("dash", "bash"),
+    ("deno", "typescript"),
+    ("escript", "erlang"),
+    ("guile", "scheme"),
+    ("js2", "javascript"),
+    ("lisp-interaction", "elisp"),
+    ("luajit", "lua"),
+    ("makefile-gmake", "make"),
+    ("mksh", "bash"),
+    ("nodejs", "javascript"),
+    ("pypy", "python"),
+    ("runghc", "haskell"),
+    ("runhaskell", "haskell"),
+    ("ts-node", "typescript"),
+    ("typescriptreact", "tsx"),
+];
+
+/// Looks up `key`, ignoring case, in `table`, then finds the language it
+/// names.
+fn lookup(table: &[(&str, &str)], key: &str) -> Option<&'static Language> {
+    table.iter()
+        .find(|(k, _)| cmp_ignore_case_ascii(k, key).is_eq())
+        .and_then(|(_, name)| Language::find_by_name(name))
+}
+
+/// Finds the language named by `name`, a shebang interpreter or a modeline
+/// file type.
+fn find_named(name: &str) -> Option<&'static Language> {
+    lookup(INTERPRETERS, name).or_else(|| Language::find(name))
+}
+
+impl Language {
+    /// Detects the language of the file at `path` with contents `content` from,
+    /// in order, a modeline, a well-known file name like `Makefile`, a shebang,
+    /// or its longest extension. No CMake grammar is bundled, so
+    /// `CMakeLists.txt` isn't detected.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jellybean::Language;
+    ///
+    /// let detect = |path, content| Language::detect(path, content).map(|l| l.name());
+    ///
+    /// assert_eq!(detect("src/main.rs", ""), Some("rust"));
+    /// assert_eq!(detect("Makefile", ""), Some("make"));
+    /// assert_eq!(detect("~/.bashrc", ""), Some("bash"));
+    /// assert_eq!(detect("index.d.ts", ""), Some("typescript"));
+    /// assert_eq!(detect("bin/serve", "#!/usr/bin/env python3\nprint(1)\n"), Some("python"));
+    /// assert_eq!(detect("notes.txt", "fn main() {}\n// vim: set ft=rust:\n"), Some("rust"));
+    /// ```
+    pub fn detect(path: impl AsRef<Path>, content: &str) -> Option<&'static Language> {
+        let file_name = path.as_ref().file_name().and_then(|name| name.to_str());
+        modeline(content)
+            .or_else(|| file_name.and_then(well_known_name))
+            .or_else(|| shebang(content))
+            .or_else(|| {
+                let candidates = extension_candidates(file_name?);
+                match candidates.len() {
+                    0 | 1 => candidates.first().copied(),
+                    _ => Language::disambiguate(&candidates, content).first().copied(),
+                }
+            })
+    }
+
+    /// Returns every language that may be the language of the file at `path`,
+    /// judging by its well-known name or longest claimed extension alone.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jellybean::Language;
+    ///
+    /// let candidates = Language::candidates_for("include/vec.h");
+    /// assert!(candidates.iter().any(|l| l.name() == "c"));
+    /// assert!(candidates.iter().any(|l| l.name() == "cpp"));
+    ///
+    /// let source = "template <typename T> class Vec { T* data; };";
+    /// assert_eq!(Language::disambiguate(&candidates, source)[0].name(), "cpp");
+    /// ```
+    pub fn candidates_for(path: impl AsRef<Path>) -> Vec<&'static Language> {
+        let file_name = match path.as_ref().file_name().and_then(|name| name.to_str()) {
+            Some(file_name) => file_name,
+            None => return vec![],
+        };
+
+        let mut candidates: Vec<&'static Language> = vec![];
+        let found = well_known_name(file_name).into_iter().chain(extension_candidates(file_name));
+        for language in found {
+            if !candidates.iter().any(|c| c.name == language.name) {
+                candidates.push(language);
+            }
+        }
+
+        candidates
+    }
+
+    /// Ranks `candidates`, best first, by the bytes and then the number of
+    /// syntax errors each reports in the first 64KiB of `source`.
+    pub fn disambiguate(candidates: &[&'static Language], source: &str) -> Vec<&'static Language> {
+        let mut ranked = candidates.iter()
+            .map(|language| (error_score(language, source), *language))
+            .collect::<Vec<_>>();
+
+        ranked.sort_by_key(|(score, _)| *score);
+        ranked.into_iter().map(|(_, language)| language).collect()
+    }
+}
+
+/// Detects a language from a Vim or Emacs modeline in `content`.
+pub(crate) fn modeline(content: &str) -> Option<&'static Language> {
+    let emacs = content.lines().take(2).find_map(emacs_modeline);
+    emacs.or_else(|| {
+        let line_count = content.lines().count();
+        content.lines()
+            .enumerate()
+            .filter(|(i, _)| *i < MODELINE_LINES || *i + MODELINE_LINES >= line_count)
+            .find_map(|(_, line)| vim_modeline(line))
+    })
+}
+
+/// Parses a Vim modeline such as `vim: set ft=rust:` or `vi: ts=4 ft=rust`.
+fn vim_modeline(line: &str) -> Option<&'static Language> {
+    let options = ["vim:", "vi:", "Vim:", "ex:"].iter().find_map(|marker| {
+        let (i, _) = line.match_indices(marker).find(|(i, _)| {
+            line[..*i].chars().next_back().is_none_or(|c| c.is_whitespace())
+        })?;
+
+        Some(&line[i + marker.len()..])
+    })?;
+
+    options.split(|c: char| c == ':' || c.is_whitespace())
+        .filter_map(|option| option.split_once('='))
+        .filter(|(key, _)| matches!(*key, "ft" | "filetype" | "syn" | "syntax"))
+        .find_map(|(_, value)| find_named(value))
+}
+
+/// Parses an Emacs modeline such as `-*- mode: rust -*-` or `-*- rust -*-`.
+fn emacs_modeline(line: &str) -> Option<&'static Language> {
+    let (_, rest) = line.split_once("-*-")?;
+    let (variables, _) = rest.split_once("-*-")?;
+    let mode = if variables.contains(':') {
+        variables.split(';')
+            .filter_map(|variable| variable.split_once(':'))
+            .find(|(key, _)| key.trim().eq_ignore_ascii_case("mode"))
+            .map(|(_, value)| value)?
+    } else {
+        variables
+    };
+
+    let mode = mode.trim();
+    find_named(mode.strip_suffix("-mode").unwrap_or(mode))
+}
+
+/// Detects a language from the interpreter in a shebang on the first line of
+/// `content`, as in `#!/bin/sh` or `#!/usr/bin/env -S python3 -u`.
+pub(crate) fn shebang(content: &str) -> Option<&'static Language> {
+    let line = content.lines().next()?.strip_prefix("#!")?;
+    let mut words = line.split_whitespace();
+    let mut interpreter = words.next()?.rsplit('/').next()?;
+    if interpreter == "env" {
+        interpreter = words.find(|w| !w.starts_with('-') && !w.contains('='))?;
+    }
+
+    // `python3.11` -> `python`, `ruby2.7` -> `ruby`.
+    let unversioned = interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    find_named(interpreter).or_else(|| find_named(unversioned))
+}
+
+/// Detects a language from a well-known file name, matched with case, or
+/// from one of the `FILE_NAME_PREFIXES` followed by a suffix.
+fn well_known_name(file_name: &str) -> Option<&'static Language> {
+    let name = match file_name.split_once('.') {
+        Some((prefix, _)) if FILE_NAME_PREFIXES.contains(&prefix) => prefix,
+        _ => file_name,
+    };
+
+    FILE_NAMES.iter()
+        .find(|(k, _)| *k == name)
+        .and_then(|(_, language)| Language::find_by_name(language))
+}
+
+/// Returns the languages claiming the longest extension of `file_name` that
+/// any language claims or, if none does, `file_name` itself.
+fn extension_candidates(file_name: &str) -> Vec<&'static Language> {
+    let claiming = |file_type: &str| all_languages()
+        .filter(|l| l.file_types.binary_search(&file_type.as_uncased()).is_ok())
+        .collect::<Vec<_>>();
+
+    let name = file_name.trim_start_matches('.');
+    name.match_indices('.')
+        .map(|(i, _)| &name[i + 1..])
+        .filter(|ext| !ext.is_empty())
+        .map(|ext| {
+            let mut candidates = claiming(ext);
+            if let Some(language) = lookup(COMPOUND_EXTENSIONS, ext) {
+                candidates.insert(0, language);
+            }
+
+            candidates
+        })
+        .find(|candidates| !candidates.is_empty())
+        .unwrap_or_else(|| claiming(file_name))
+}
+
+/// The bundled languages followed by the registered languages.
+fn all_languages() -> impl Iterator<Item = &'static Language> {
+    ALL_LANGUAGES.iter().copied().chain(Language::registered())
+}
+
+/// Returns the bytes in `source`, up to `TRIAL_PARSE_BYTES`, that are covered
+/// by syntax errors when parsed as `language`, then the number of errors.
+pub(crate) fn error_score(language: &Language, source: &str) -> (usize, usize) {
+    let mut end = source.len().min(TRIAL_PARSE_BYTES);
+    while !source.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    match language.parse_errors(&source[..end]) {
+        Ok(errors) => {
+            let bytes = errors.iter().map(|e| e.range.len().max(1)).sum();
+            (bytes, errors.len())
+        },
+        Err(_) => (usize::MAX, usize::MAX),
+    }
+}
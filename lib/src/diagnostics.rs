@@ -0,0 +1,74 @@
+use std::fmt;
+use std::ops::Range;
+
+use tree_sitter::{Point, Tree};
+
+/// A syntax error in parsed source.
+///
+/// Highlighting of source containing syntax errors is best-effort: the
+/// highlights in and around a diagnostic's range may be wrong.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// What kind of error this is.
+    pub kind: DiagnosticKind,
+    /// The byte range of the error in the source. Empty for missing nodes.
+    pub range: Range<usize>,
+    /// The zero-based row and byte column where the error starts.
+    pub start: Point,
+    /// The zero-based row and byte column where the error ends.
+    pub end: Point,
+}
+
+/// The kind of a [`Diagnostic`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticKind {
+    /// Source that couldn't be parsed, i.e, an `ERROR` node.
+    Error,
+    /// A node that the parser expected but was absent, i.e, a `MISSING` node,
+    /// along with the kind of the node that was expected.
+    Missing(&'static str),
+}
+
+/// Collects the `ERROR` and `MISSING` nodes in `tree`, in source order.
+///
+/// Errors nested within an `ERROR` node are not reported separately.
+pub(crate) fn collect(tree: &Tree) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    let mut cursor = tree.walk();
+    loop {
+        let node = cursor.node();
+        let kind = match () {
+            _ if node.is_missing() => Some(DiagnosticKind::Missing(node.kind())),
+            _ if node.is_error() => Some(DiagnosticKind::Error),
+            _ => None,
+        };
+
+        if let Some(kind) = kind {
+            diagnostics.push(Diagnostic {
+                kind,
+                range: node.byte_range(),
+                start: node.start_position(),
+                end: node.end_position(),
+            });
+        } else if node.has_error() && cursor.goto_first_child() {
+            continue;
+        }
+
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                return diagnostics;
+            }
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    /// Formats the diagnostic as a message with a one-based line and column.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (line, column) = (self.start.row + 1, self.start.column + 1);
+        match self.kind {
+            DiagnosticKind::Error => write!(f, "syntax error at {line}:{column}"),
+            DiagnosticKind::Missing(kind) => write!(f, "missing `{kind}` at {line}:{column}"),
+        }
+    }
+}
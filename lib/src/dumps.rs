@@ -2,35 +2,60 @@ use std::sync::OnceLock;
 
 use tree_sitter_highlight::HighlightConfiguration;
 
-use crate::{Language, Highlighter, EXHAUSTIVE_CAPTURES};
+use crate::{Language, Highlighter, Error, EXHAUSTIVE_CAPTURES};
+
+type Result<T, E = Error> = std::result::Result<T, E>;
 
 pub struct Dump {
     bytes: &'static [u8],
-    cache: &'static OnceLock<HighlightConfiguration>,
+    cache: &'static OnceLock<Result<HighlightConfiguration>>,
 }
 
 impl Dump {
     #[inline(always)]
-    pub fn force(&self, lang: &'static Language) -> Highlighter {
-        let config = self.cache.get_or_init(|| self.decode(lang));
-        Highlighter::new(lang, config, &EXHAUSTIVE_CAPTURES[..])
+    pub fn force(&self, lang: &'static Language) -> Result<Highlighter> {
+        Ok(Highlighter::new(lang, self.config(lang)?, &EXHAUSTIVE_CAPTURES[..]))
+    }
+
+    #[inline(always)]
+    pub fn config(&self, lang: &'static Language) -> Result<&'static HighlightConfiguration> {
+        self.cache.get_or_init(|| self.decode(lang)).as_ref().map_err(|e| e.clone())
     }
 
     #[inline(always)]
-    pub fn decode(&self, lang: &'static Language) -> HighlightConfiguration {
-        let bytes = bincode::deserialize(self.bytes).unwrap();
-        HighlightConfiguration::deserialize(bytes, lang.raw()).unwrap()
+    pub fn decode(&self, lang: &'static Language) -> Result<HighlightConfiguration> {
+        let error = |message| Error::Decode { language: lang.name.into(), message };
+        let bytes = bincode::deserialize(self.bytes).map_err(|e| error(e.to_string()))?;
+        HighlightConfiguration::deserialize(bytes, lang.raw()).map_err(|e| error(format!("{e:?}")))
+    }
+}
+
+/// Returns the dump for `language` if it has one and it's up-to-date.
+#[inline(always)]
+fn dump(language: &'static Language) -> Option<&'static Dump> {
+    DUMPS.get(language.dump_id?)
+}
+
+#[inline(always)]
+pub fn fetch_config(language: &'static Language) -> Result<HighlightConfiguration> {
+    match dump(language) {
+        Some(dump) => dump.decode(language),
+        None => language.highlight_config(&EXHAUSTIVE_CAPTURES[..]),
     }
 }
 
+/// Returns the cached precached configuration for `language`, if it has one.
 #[inline(always)]
-pub fn fetch_config(language: &'static Language) -> HighlightConfiguration {
-    DUMPS[language.dump_id].decode(language)
+pub fn cached_config(language: &'static Language) -> Option<Result<&'static HighlightConfiguration>> {
+    dump(language).map(|dump| dump.config(language))
 }
 
 #[inline(always)]
-pub fn fetch_highlighter(language: &'static Language) -> Highlighter {
-    DUMPS[language.dump_id].force(language)
+pub fn fetch_highlighter(language: &'static Language) -> Result<Highlighter> {
+    match dump(language) {
+        Some(dump) => dump.force(language),
+        None => language.custom_highlighter(&EXHAUSTIVE_CAPTURES[..]),
+    }
 }
 
 macro_rules! define_dump_ids {
@@ -54,7 +79,7 @@ macro_rules! define_dumps {
             Dump {
                 bytes: &crate::precached::$m::DUMP,
                 cache: {
-                    static CACHE: OnceLock<HighlightConfiguration> = OnceLock::new();
+                    static CACHE: OnceLock<Result<HighlightConfiguration>> = OnceLock::new();
                     &CACHE
                 }
             }
@@ -0,0 +1,85 @@
+use crate::Language;
+
+impl Language {
+    /// Returns the markers that begin a line comment, the preferred marker
+    /// first. Empty if the language has no line comments.
+    ///
+    /// # Example
+    ///
+    /// Toggling a line comment:
+    ///
+    /// ```rust
+    /// use jellybean::Language;
+    ///
+    /// fn toggle_comment(language: &Language, line: &str) -> String {
+    ///     let Some(marker) = language.line_comments().first() else {
+    ///         return line.to_string();
+    ///     };
+    ///
+    ///     let indent = line.len() - line.trim_start().len();
+    ///     let (indent, code) = line.split_at(indent);
+    ///     match code.strip_prefix(marker) {
+    ///         Some(code) => format!("{indent}{}", code.strip_prefix(' ').unwrap_or(code)),
+    ///         None => format!("{indent}{marker} {code}"),
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(toggle_comment(&Language::rust, "    let x = 1;"), "    // let x = 1;");
+    /// assert_eq!(toggle_comment(&Language::python, "# x = 1"), "x = 1");
+    /// ```
+    pub fn line_comments(&self) -> &[&str] {
+        self.info.line_comments
+    }
+
+    /// Returns the `(open, close)` markers of a block comment, if the language
+    /// has block comments.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jellybean::Language;
+    ///
+    /// assert_eq!(Language::c.block_comment(), Some(("/*", "*/")));
+    /// assert_eq!(Language::python.block_comment(), None);
+    /// ```
+    pub fn block_comment(&self) -> Option<(&str, &str)> {
+        self.info.block_comment
+    }
+
+    /// Returns the `(open, close)` bracket pairs to match and auto-close.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jellybean::Language;
+    ///
+    /// let closing = |language: &Language, open| language.brackets()
+    ///     .iter()
+    ///     .find(|(o, _)| *o == open)
+    ///     .map(|(_, close)| *close);
+    ///
+    /// assert_eq!(closing(&Language::rust, "{"), Some("}"));
+    /// assert_eq!(closing(&Language::html, "<"), Some(">"));
+    /// assert_eq!(closing(&Language::rust, "<"), None);
+    /// ```
+    pub fn brackets(&self) -> &[(&str, &str)] {
+        self.info.brackets
+    }
+
+    /// Returns one level of indentation in the language's conventional style:
+    /// either `"\t"` or a number of spaces. Four spaces for languages created
+    /// at runtime.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jellybean::Language;
+    ///
+    /// assert_eq!(Language::go.indent(), "\t");
+    /// assert_eq!(Language::rust.indent(), "    ");
+    /// assert_eq!(Language::javascript.indent(), "  ");
+    /// ```
+    pub fn indent(&self) -> &str {
+        self.info.indent
+    }
+}
@@ -0,0 +1,151 @@
+use std::fmt;
+
+use tree_sitter::{QueryError, QueryErrorKind};
+
+/// An error that occurs while configuring, parsing, or highlighting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// A query failed to compile.
+    Query {
+        /// The name of the language the query is for.
+        language: String,
+        /// The name of the query: `"highlights"`, `"injections"`, etc.
+        query: &'static str,
+        /// The zero-based row in the query where the error occurred.
+        row: usize,
+        /// The zero-based column in the query where the error occurred.
+        column: usize,
+        /// A description of the error.
+        message: String,
+    },
+    /// A language doesn't bundle a query that's required.
+    MissingQuery {
+        /// The name of the language missing the query.
+        language: String,
+        /// The name of the missing query.
+        query: &'static str,
+    },
+    /// A precached highlight configuration failed to decode.
+    Decode {
+        /// The name of the language whose configuration failed to decode.
+        language: String,
+        /// A description of the error.
+        message: String,
+    },
+    /// A highlight configuration failed to serialize.
+    Serialize {
+        /// The name of the language whose configuration failed to serialize.
+        language: String,
+        /// A description of the error.
+        message: String,
+    },
+    /// Highlighting was cancelled by a cancellation flag or a timeout.
+    ///
+    /// Every event emitted before this error is valid and, together, the
+    /// events cover the source up to byte `offset`.
+    Cancelled {
+        /// The byte offset up to which the source was highlighted.
+        offset: usize,
+    },
+    /// A language is incompatible with the linked tree-sitter library.
+    InvalidLanguage {
+        /// The name of the incompatible language.
+        language: String,
+    },
+    /// An injected language is incompatible with the linked tree-sitter
+    /// library or its configuration failed to build.
+    InvalidInjection {
+        /// The name of the injection as it appears in the source.
+        name: String,
+        /// A description of the error.
+        message: String,
+    },
+    /// A language couldn't be created because another language has the same
+    /// name.
+    DuplicateLanguage {
+        /// The name of the language.
+        name: String,
+    },
+    /// A highlight referred to a capture the highlighter doesn't recognize.
+    ///
+    /// This occurs when an [`Injection::Config`](crate::Injection::Config) is
+    /// configured with captures other than the highlighter's.
+    UnknownCapture {
+        /// The index of the unrecognized capture.
+        index: usize,
+    },
+    /// A source is too large to be processed.
+    SourceTooLarge {
+        /// The length of the source in bytes.
+        len: usize,
+        /// The maximum supported length in bytes.
+        max: usize,
+    },
+    /// A grammar or its queries failed to load.
+    Load {
+        /// The path of the file or directory that failed to load.
+        path: std::path::PathBuf,
+        /// A description of the error.
+        message: String,
+    },
+    /// An unknown error occurred.
+    Unknown,
+}
+
+impl Error {
+    pub(crate) fn query(language: &str, query: &'static str, error: QueryError) -> Self {
+        if error.kind == QueryErrorKind::Language {
+            return Error::InvalidLanguage { language: language.into() };
+        }
+
+        Error::Query {
+            language: language.into(),
+            query,
+            row: error.row,
+            column: error.column,
+            message: error.message,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Query { language, query, row, column, message } => {
+                let (row, column) = (row + 1, column + 1);
+                write!(f, "{language} {query} query error at {row}:{column}: {message}")
+            }
+            Error::MissingQuery { language, query } => {
+                write!(f, "{language} is missing a {query} query")
+            }
+            Error::Decode { language, message } => {
+                write!(f, "failed to decode {language} configuration: {message}")
+            }
+            Error::Serialize { language, message } => {
+                write!(f, "failed to serialize {language} configuration: {message}")
+            }
+            Error::Cancelled { offset } => write!(f, "cancelled after {offset} bytes"),
+            Error::InvalidLanguage { language } => {
+                write!(f, "incompatible tree-sitter language `{language}`")
+            }
+            Error::InvalidInjection { name, message } => {
+                write!(f, "invalid injection `{name}`: {message}")
+            }
+            Error::DuplicateLanguage { name } => {
+                write!(f, "a language named `{name}` already exists")
+            }
+            Error::UnknownCapture { index } => {
+                write!(f, "highlight refers to unknown capture {index}")
+            }
+            Error::SourceTooLarge { len, max } => {
+                write!(f, "source of {len} bytes exceeds the maximum of {max} bytes")
+            }
+            Error::Load { path, message } => {
+                write!(f, "failed to load {}: {message}", path.display())
+            }
+            Error::Unknown => write!(f, "unknown error"),
+        }
+    }
+}
+
+impl std::error::Error for Error { }
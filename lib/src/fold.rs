@@ -0,0 +1,116 @@
+use std::collections::HashSet;
+use std::ops::Range;
+
+use tree_sitter::{Node, Query, QueryCursor, Tree};
+
+use crate::Language;
+
+/// A collapsible region of source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fold {
+    /// The byte range of the region in the source.
+    pub range: Range<usize>,
+    /// The zero-based line the region starts on.
+    pub start_line: usize,
+    /// The zero-based line the region ends on. Always after `start_line`.
+    pub end_line: usize,
+    /// The number of folds this fold is nested in. Top-level folds have a
+    /// depth of `0`.
+    pub depth: usize,
+}
+
+/// The pairs of brackets used for folding when a language has no usable
+/// `folds` query.
+const BRACKETS: &[(&str, &str)] = &[("{", "}"), ("[", "]"), ("(", ")")];
+
+/// Returns the folds in `tree`, parsed from `source`, ordered by start, with
+/// enclosing folds before the folds they enclose.
+pub(crate) fn collect(language: &Language, tree: &Tree, source: &str) -> Vec<Fold> {
+    // A `folds` query that fails to compile is treated as missing.
+    let query = language.find_query("folds")
+        .and_then(|query| Query::new(language.raw(), query).ok());
+
+    let mut folds = match query {
+        Some(query) => query_folds(&query, tree, source),
+        None => {
+            let mut folds = vec![];
+            bracket_folds(tree.root_node(), &mut folds);
+            folds
+        }
+    };
+
+    // Only keep one fold per line range, preferring the outermost.
+    folds.retain(|fold| fold.end_line > fold.start_line);
+    folds.sort_by_key(|fold| (fold.range.start, std::cmp::Reverse(fold.range.end)));
+    let mut seen = HashSet::new();
+    folds.retain(|fold| seen.insert((fold.start_line, fold.end_line)));
+
+    let mut enclosing: Vec<usize> = vec![];
+    for fold in &mut folds {
+        while enclosing.last().is_some_and(|&end| end <= fold.range.start) {
+            enclosing.pop();
+        }
+
+        fold.depth = enclosing.len();
+        enclosing.push(fold.range.end);
+    }
+
+    folds
+}
+
+/// Folds the nodes captured as `@fold` by the `folds` query `query`.
+fn query_folds(query: &Query, tree: &Tree, source: &str) -> Vec<Fold> {
+    let fold_index = match query.capture_index_for_name("fold") {
+        Some(index) => index,
+        None => return vec![],
+    };
+
+    let mut cursor = QueryCursor::new();
+    cursor.captures(query, tree.root_node(), source.as_bytes())
+        .map(|(m, i)| m.captures[i])
+        .filter(|capture| capture.index == fold_index)
+        .map(|capture| fold(capture.node, capture.node))
+        .collect()
+}
+
+/// Folds the regions between matching bracket tokens that are siblings.
+fn bracket_folds(node: Node<'_>, folds: &mut Vec<Fold>) {
+    let mut open: Vec<(Node<'_>, &str)> = vec![];
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.is_named() {
+            if child.child_count() > 0 {
+                bracket_folds(child, folds);
+            }
+
+            continue;
+        }
+
+        let kind = child.kind();
+        if let Some((_, close)) = BRACKETS.iter().find(|(open, _)| *open == kind) {
+            open.push((child, close));
+        } else if let Some(i) = open.iter().rposition(|(_, close)| *close == kind) {
+            folds.push(fold(open[i].0, child));
+            open.truncate(i);
+        } else if child.child_count() > 0 {
+            bracket_folds(child, folds);
+        }
+    }
+}
+
+fn fold(start: Node<'_>, end: Node<'_>) -> Fold {
+    // A node that ends with a line terminator ends at column 0 of the next
+    // line, but that line isn't part of the fold.
+    let end_position = end.end_position();
+    let end_line = match end_position.column {
+        0 => end_position.row.saturating_sub(1),
+        _ => end_position.row,
+    };
+
+    Fold {
+        range: start.start_byte()..end.end_byte(),
+        start_line: start.start_position().row,
+        end_line,
+        depth: 0,
+    }
+}
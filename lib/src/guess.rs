@@ -0,0 +1,221 @@
+use std::collections::HashMap;
+
+use crate::Language;
+use crate::detect::{error_score, modeline, shebang, TRIAL_PARSE_BYTES};
+
+/// The maximum number of languages, ranked by token statistics, that parse a
+/// source when guessing its language.
+const MAX_TRIAL_PARSES: usize = 8;
+
+/// Weighted tokens characteristic of the most common languages.
+///
+/// Tokens made only of word characters match whole words, case-sensitively.
+/// Other tokens match anywhere. A `\n` prefix matches the start of a line.
+static SIGNATURES: &[(&str, &[(&str, f32)])] = &[
+    ("bash", &[
+        ("fi", 3.0), ("esac", 3.0), ("then", 2.0), ("elif", 1.0), ("done", 1.5),
+        ("echo", 2.0), ("export", 2.0), ("local", 1.0), ("$(", 2.0), ("${", 2.0),
+        ("[[", 2.0), ("&&", 0.5), ("\n#!", 1.0),
+    ]),
+    ("c", &[
+        ("#include", 3.0), ("printf", 2.0), ("malloc", 3.0), ("free", 1.0),
+        ("typedef", 2.0), ("struct", 1.5), ("NULL", 2.0), ("sizeof", 2.0),
+        ("void", 1.0), ("int", 1.0), ("char", 1.0), ("->", 1.0), ("#define", 2.0),
+    ]),
+    ("c_sharp", &[
+        ("namespace", 2.0), ("using", 2.0), ("public", 1.0), ("static", 0.5),
+        ("void", 0.5), ("string", 1.0), ("var", 1.0), ("Console", 3.0),
+        ("get;", 3.0), ("set;", 3.0), ("async", 0.5), ("await", 0.5),
+    ]),
+    ("cpp", &[
+        ("#include", 2.0), ("std::", 4.0), ("template", 3.0), ("typename", 3.0),
+        ("namespace", 2.0), ("class", 1.0), ("public:", 3.0), ("private:", 3.0),
+        ("nullptr", 3.0), ("auto", 1.0), ("cout", 3.0), ("::", 1.0), ("const", 0.5),
+    ]),
+    ("css", &[
+        ("px;", 3.0), ("em;", 2.0), ("color:", 3.0), ("margin", 2.0),
+        ("padding", 2.0), ("display:", 3.0), ("font-", 2.0), ("@media", 3.0),
+        ("!important", 3.0), ("background", 1.5),
+    ]),
+    ("dockerfile", &[
+        ("\nFROM ", 4.0), ("\nRUN ", 4.0), ("\nCOPY ", 3.0), ("\nWORKDIR ", 4.0),
+        ("\nCMD ", 3.0), ("\nENTRYPOINT ", 4.0), ("\nEXPOSE ", 4.0), ("\nENV ", 2.0),
+    ]),
+    ("elixir", &[
+        ("defmodule", 5.0), ("defp", 4.0), ("do", 1.0), ("end", 1.0), ("|>", 2.0),
+        ("fn", 0.5), ("->", 0.5), ("@doc", 3.0), ("%{", 2.0), (":ok", 3.0),
+    ]),
+    ("go", &[
+        ("func", 3.0), ("package", 2.0), ("import", 1.0), (":=", 3.0), ("chan", 3.0),
+        ("defer", 3.0), ("go", 1.0), ("nil", 2.0), ("fmt", 3.0), ("struct", 1.0),
+        ("interface", 1.0), ("err", 1.5), ("range", 1.0),
+    ]),
+    ("haskell", &[
+        ("where", 1.5), ("module", 1.5), ("import", 0.5), ("data", 1.5),
+        ("deriving", 4.0), ("instance", 2.0), ("::", 1.5), ("->", 0.5), ("<-", 1.5),
+        ("Maybe", 3.0), ("IO", 2.0), ("let", 0.5), ("\nmain =", 3.0),
+    ]),
+    ("html", &[
+        ("<!DOCTYPE", 5.0), ("<html", 4.0), ("<div", 3.0), ("</div>", 3.0),
+        ("<span", 2.0), ("<p>", 2.0), ("class=\"", 2.0), ("href=", 2.0),
+        ("<body", 3.0), ("<head", 3.0), ("</", 1.0),
+    ]),
+    ("java", &[
+        ("public", 1.5), ("private", 1.0), ("class", 1.0), ("static", 0.5),
+        ("void", 1.0), ("extends", 2.0), ("implements", 2.0), ("import", 0.5),
+        ("System", 3.0), ("String", 1.5), ("new", 0.5), ("@Override", 4.0),
+        ("final", 1.0), ("package", 1.0),
+    ]),
+    ("javascript", &[
+        ("function", 2.0), ("const", 1.0), ("let", 0.5), ("var", 1.0), ("=>", 1.5),
+        ("console", 3.0), ("require", 3.0), ("undefined", 2.0), ("document", 2.0),
+        ("===", 3.0), ("export", 0.5), ("async", 0.5), ("module", 1.0),
+    ]),
+    ("json", &[
+        ("\": ", 2.0), ("{\"", 2.0), ("\",", 1.0), ("true", 0.5), ("false", 0.5),
+        ("null", 0.5), ("[{", 1.0),
+    ]),
+    ("kotlin", &[
+        ("fun", 4.0), ("val", 2.0), ("var", 0.5), ("data", 1.0), ("object", 1.0),
+        ("when", 1.5), ("println", 1.0), ("override", 1.0), ("companion", 4.0),
+        ("?.", 1.5), ("!!", 1.5),
+    ]),
+    ("lua", &[
+        ("local", 2.0), ("function", 1.0), ("end", 1.5), ("then", 1.0), ("elseif", 3.0),
+        ("nil", 1.5), ("~=", 3.0), ("..", 1.0), ("require", 1.0), ("pairs", 3.0),
+        ("ipairs", 4.0), ("--", 0.5),
+    ]),
+    ("markdown", &[
+        ("\n# ", 3.0), ("\n## ", 3.0), ("\n- ", 1.0), ("\n* ", 1.0), ("```", 3.0),
+        ("](", 3.0), ("**", 1.5), ("\n> ", 1.5), ("\n1. ", 1.0),
+    ]),
+    ("php", &[
+        ("<?php", 8.0), ("$this", 4.0), ("->", 1.0), ("echo", 1.0), ("function", 1.0),
+        ("namespace", 0.5), ("public", 0.5), ("array", 1.5), ("=>", 0.5), ("::", 0.5),
+    ]),
+    ("python", &[
+        ("def", 3.0), ("self", 2.0), ("import", 1.0), ("from", 1.0), ("elif", 3.0),
+        ("None", 2.0), ("True", 1.5), ("False", 1.5), ("print", 1.0), ("lambda", 2.0),
+        ("__init__", 4.0), ("in", 0.5), ("not", 0.5), ("\"\"\"", 2.0), ("):\n", 2.0),
+    ]),
+    ("ruby", &[
+        ("def", 1.5), ("end", 2.0), ("require", 1.5), ("puts", 3.0), ("attr_accessor", 5.0),
+        ("module", 1.0), ("do", 1.0), ("nil", 1.5), ("elsif", 4.0), ("unless", 2.0),
+        ("@", 0.5), ("|", 0.5),
+    ]),
+    ("rust", &[
+        ("fn", 3.0), ("let", 1.0), ("mut", 3.0), ("impl", 3.0), ("pub", 2.0),
+        ("use", 1.0), ("struct", 1.0), ("enum", 1.0), ("match", 1.5), ("Self", 2.0),
+        ("::", 1.0), ("->", 0.5), ("&self", 3.0), ("println!", 4.0), ("Option", 2.0),
+        ("Some", 2.0), ("crate", 2.0), ("#[", 2.0),
+    ]),
+    ("sql", &[
+        ("SELECT", 4.0), ("FROM", 2.0), ("WHERE", 3.0), ("INSERT", 3.0), ("INTO", 2.0),
+        ("UPDATE", 2.0), ("JOIN", 3.0), ("CREATE", 2.0), ("TABLE", 2.0), ("VALUES", 2.0),
+        ("select", 2.0), ("from", 0.5), ("where", 1.0),
+    ]),
+    ("swift", &[
+        ("func", 2.0), ("let", 1.0), ("var", 1.0), ("guard", 4.0), ("import", 0.5),
+        ("struct", 1.0), ("protocol", 3.0), ("extension", 3.0), ("self", 0.5),
+        ("nil", 1.0), ("->", 0.5), ("\\(", 3.0), ("init", 1.5),
+    ]),
+    ("toml", &[
+        ("\n[", 2.0), ("]\n", 1.0), (" = \"", 2.0), ("[[", 1.0), (" = true", 1.5),
+        (" = false", 1.5),
+    ]),
+    ("typescript", &[
+        ("interface", 3.0), ("type", 1.0), ("implements", 1.0), ("readonly", 3.0),
+        ("enum", 1.0), ("namespace", 1.0), ("declare", 3.0), (": string", 4.0),
+        (": number", 4.0), (": boolean", 4.0), ("as", 0.5), ("export", 0.5),
+        ("const", 0.5), ("=>", 0.5),
+    ]),
+    ("xml", &[
+        ("<?xml", 8.0), ("xmlns", 4.0), ("</", 1.0), ("/>", 1.0), ("<!--", 1.0),
+    ]),
+];
+
+impl Language {
+    /// Guesses the language of `source` from its contents alone, returning
+    /// candidates with confidences summing to `1.0`, most likely first. Prefer
+    /// [`Language::detect()`] when a file name is known.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jellybean::Language;
+    ///
+    /// let guesses = Language::guess("fn main() {\n    let mut v = Vec::new();\n    v.push(1);\n}\n");
+    /// assert_eq!(guesses[0].0.name(), "rust");
+    ///
+    /// let guesses = Language::guess("def greet(name):\n    print(f\"hi {name}\")\n");
+    /// assert_eq!(guesses[0].0.name(), "python");
+    /// assert!(guesses.iter().all(|(_, confidence)| (0.0..=1.0).contains(confidence)));
+    /// ```
+    pub fn guess(source: &str) -> Vec<(&'static Language, f32)> {
+        if source.trim().is_empty() {
+            return vec![];
+        }
+
+        if let Some(language) = modeline(source).or_else(|| shebang(source)) {
+            return vec![(language, 1.0)];
+        }
+
+        let mut scores = token_scores(source);
+        scores.retain(|(_, score)| *score > 0.0);
+        scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scores.truncate(MAX_TRIAL_PARSES);
+
+        let len = source.len().min(TRIAL_PARSE_BYTES) as f32;
+        for (language, score) in &mut scores {
+            let (error_bytes, _) = error_score(language, source);
+            let quality = 1.0 - (error_bytes as f32 / len).min(1.0);
+            *score *= quality * quality;
+        }
+
+        let total: f32 = scores.iter().map(|(_, score)| score).sum();
+        if total == 0.0 {
+            return vec![];
+        }
+
+        scores.retain(|(_, score)| *score > 0.0);
+        scores.iter_mut().for_each(|(_, score)| *score /= total);
+        scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scores
+    }
+}
+
+/// Scores each enabled language in `SIGNATURES` by the tokens of its
+/// signature in `source`, each weighted by the log of its frequency.
+fn token_scores(source: &str) -> Vec<(&'static Language, f32)> {
+    let mut words: HashMap<&str, usize> = HashMap::new();
+    source.split(|c: char| !is_word_char(c))
+        .filter(|word| !word.is_empty())
+        .for_each(|word| *words.entry(word).or_default() += 1);
+
+    let lines = format!("\n{source}");
+    SIGNATURES.iter()
+        .filter_map(|(name, tokens)| {
+            let language = Language::find_by_name(name)?;
+            let score = tokens.iter()
+                .map(|(token, weight)| {
+                    let count = if token.chars().all(is_word_char) {
+                        words.get(token).copied().unwrap_or(0)
+                    } else {
+                        lines.matches(token).count()
+                    };
+
+                    match count {
+                        0 => 0.0,
+                        n => weight * (1.0 + (n as f32).ln()),
+                    }
+                })
+                .sum();
+
+            Some((language, score))
+        })
+        .collect()
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
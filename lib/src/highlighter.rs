@@ -1,19 +1,77 @@
+use std::ops::Range;
+use std::cell::{Cell, OnceCell};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use tree_sitter::{LANGUAGE_VERSION, MIN_COMPATIBLE_LANGUAGE_VERSION};
 use tree_sitter_highlight::{HighlightConfiguration, HighlightEvent};
 use tree_sitter_highlight::Highlighter as TsHighlighter;
 
-use crate::Language;
+use crate::{Language, Error, Capture};
+use crate::span::{Line, Lines, Span, Spans};
+
+type Result<T, E = Error> = std::result::Result<T, E>;
+
+type TsResult<T> = std::result::Result<T, tree_sitter_highlight::Error>;
+
+/// How many events to emit between checks for cancellation.
+const CANCELLATION_CHECK_INTERVAL: usize = 100;
 
-type Result<T, E = tree_sitter_highlight::Error> = std::result::Result<T, E>;
+/// Configurations built for injected languages, shared by every highlighter,
+/// by language name and recognized captures.
+static INJECTION_CONFIGS: Mutex<BTreeMap<(&str, Vec<String>), Arc<HighlightConfiguration>>>
+    = Mutex::new(BTreeMap::new());
 
 pub struct Highlighter {
     language: &'static Language,
     captures: Captures,
     config: Source<HighlightConfiguration>,
     inner: TsHighlighter,
-    // TODO: Make injection configurable.
-    // injector: Option<Box<dyn FnMut(&str) -> Option<&HighlightConfiguration>>>,
+    cancellation_flag: Option<Arc<AtomicUsize>>,
+    timeout: Option<Duration>,
+    injector: Option<Box<Injector>>,
+    injections: InjectionCache,
 }
 
+type Injector = dyn FnMut(&str) -> Option<Injection> + Send;
+
+/// How to highlight an injected language.
+///
+/// Returned by an injection resolver set via [`Highlighter::set_injector()`].
+pub enum Injection {
+    /// Highlight the injection as the language `Language`.
+    ///
+    /// Configurations are built once per language and captures, and shared.
+    Language(&'static Language),
+    /// Highlight the injection with a custom configuration, which must be
+    /// configured with the highlighter's [captures](Highlighter::captures()).
+    Config(Arc<HighlightConfiguration>),
+}
+
+/// Injection configurations resolved so far, by injection name.
+///
+/// The cache is an append-only list of boxed entries, so entries never move
+/// or drop while the cache is borrowed and configurations can be handed out
+/// for as long as it is.
+#[derive(Default)]
+struct InjectionCache {
+    head: OnceCell<Box<InjectionEntry>>,
+    /// The error for an injection that failed to resolve since it was last
+    /// taken.
+    failed: Cell<Option<Error>>,
+}
+
+struct InjectionEntry {
+    name: String,
+    /// `Ok(None)` if the injection is left unhighlighted.
+    config: Result<Option<InjectionConfig>>,
+    next: OnceCell<Box<InjectionEntry>>,
+}
+
+type InjectionConfig = Source<Arc<HighlightConfiguration>, &'static HighlightConfiguration>;
+
 #[derive(Debug)]
 pub enum Highlight<'a> {
     Start {
@@ -30,6 +88,30 @@ pub enum Highlight<'a> {
     End,
 }
 
+impl Highlight<'_> {
+    /// Returns the [`Capture`] named by the group of a `Start` event, if
+    /// this is a `Start` event and its group is in the standard taxonomy.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jellybean::{Language, Highlight, Capture, COMMON_CAPTURES};
+    ///
+    /// let mut highlighter = Language::rust.custom_highlighter(COMMON_CAPTURES).unwrap();
+    /// let captures = highlighter.highlight("fn main() {}")
+    ///     .filter_map(|event| event.unwrap().capture())
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert!(captures.contains(&Capture::Function));
+    /// ```
+    pub fn capture(&self) -> Option<Capture> {
+        match self {
+            Highlight::Start { group, .. } => Capture::from_name(group),
+            _ => None,
+        }
+    }
+}
+
 type Captures = Source<Vec<String>, &'static [&'static str]>;
 
 /// Iterator of highlight events coupled with
@@ -37,6 +119,25 @@ struct FusedEvents<'a, I> {
     captures: &'a Captures,
     source: &'a str,
     events: I,
+    /// The name of the highlighted language.
+    language: &'static str,
+    injections: &'a InjectionCache,
+    /// The end of the last emitted `Source` event.
+    offset: usize,
+    flag: Option<&'a AtomicUsize>,
+    deadline: Option<Instant>,
+    /// Events emitted since cancellation was last checked.
+    count: usize,
+    done: bool,
+}
+
+/// Iterator of highlight events restricted to a byte window of the source.
+struct Windowed<'a, I> {
+    events: I,
+    window: Range<usize>,
+    /// The currently open highlights and whether they've been emitted.
+    stack: Vec<(&'a str, usize, bool)>,
+    pending: VecDeque<Highlight<'a>>,
     done: bool,
 }
 
@@ -51,7 +152,10 @@ impl Highlighter {
             config: config.into(),
             captures: captures.into(),
             inner: TsHighlighter::new(),
-            // injector: None,
+            cancellation_flag: None,
+            timeout: None,
+            injector: None,
+            injections: InjectionCache::default(),
         }
     }
 
@@ -59,55 +163,417 @@ impl Highlighter {
         self.language
     }
 
+    /// Returns the names of the captures recognized by this highlighter.
+    ///
+    /// The `index` of a [`Highlight::Start`] is an index into this list.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jellybean::{Language, COMMON_CAPTURES};
+    ///
+    /// let highlighter = Language::rust.custom_highlighter(COMMON_CAPTURES).unwrap();
+    /// assert!(highlighter.captures().eq(COMMON_CAPTURES.iter().copied()));
+    /// ```
+    pub fn captures(&self) -> impl ExactSizeIterator<Item = &str> + '_ {
+        self.captures.iter()
+    }
+
+    /// Sets a flag that, when non-zero, cancels highlighting with an
+    /// [`Error::Cancelled`]. The highlighter never resets the flag.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    ///
+    /// use jellybean::{Language, Error, COMMON_CAPTURES};
+    ///
+    /// let flag = Arc::new(AtomicUsize::new(0));
+    /// let mut highlighter = Language::rust.custom_highlighter(COMMON_CAPTURES).unwrap();
+    /// highlighter.set_cancellation_flag(Some(flag.clone()));
+    ///
+    /// flag.store(1, Ordering::Relaxed);
+    /// let result = highlighter.highlight("fn main() {}").collect::<Result<Vec<_>, _>>();
+    /// assert!(matches!(result, Err(Error::Cancelled { .. })));
+    /// ```
+    pub fn set_cancellation_flag(&mut self, flag: Option<Arc<AtomicUsize>>) {
+        self.cancellation_flag = flag;
+    }
+
+    /// Sets the maximum duration of a call to a highlighting method, including
+    /// iteration, after which highlighting fails with [`Error::Cancelled`].
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        let micros = timeout.map_or(0, |t| t.as_micros().try_into().unwrap_or(u64::MAX));
+        self.inner.parser().set_timeout_micros(micros);
+        self.timeout = timeout;
+    }
+
+    /// Sets the resolver mapping injected language names, like a Markdown
+    /// fence's, to an [`Injection`], or `None` to leave them unhighlighted.
+    /// By default, names are resolved via [`Language::find()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jellybean::{Language, Injection, COMMON_CAPTURES};
+    ///
+    /// let mut highlighter = Language::markdown.custom_highlighter(COMMON_CAPTURES).unwrap();
+    ///
+    /// // Highlight `js` fences as JavaScript, leave others unhighlighted.
+    /// highlighter.set_injector(|name| match name {
+    ///     "js" => Some(Injection::Language(&Language::javascript)),
+    ///     _ => None,
+    /// });
+    ///
+    /// // Disable injections entirely.
+    /// highlighter.set_injector(|_| None);
+    /// ```
+    pub fn set_injector<F>(&mut self, injector: F)
+        where F: FnMut(&str) -> Option<Injection> + Send + 'static
+    {
+        self.injector = Some(Box::new(injector));
+        self.injections = InjectionCache::default();
+    }
+
     pub fn highlight<'a>(
         &'a mut self,
         source: &'a str,
     ) -> impl Iterator<Item = Result<Highlight<'a>>> + 'a {
+        // A previously cancelled parse would otherwise be resumed.
+        self.inner.parser().reset();
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        let flag = self.cancellation_flag.as_deref();
         let captures = &self.captures;
         let ts_config = self.config.inner();
-        let events = self.inner.highlight(ts_config, source.as_bytes(), None, |name| {
-            #[cfg(feature = "precached")] {
-                Language::find(name).and_then(|l| l.highlighter().config.cached().copied())
+        let (injector, injections) = (&mut self.injector, &self.injections);
+        injections.failed.take();
+        let language = self.language.name;
+        let events = self.inner.highlight(ts_config, source.as_bytes(), flag, move |name| {
+            injections.resolve(name, captures, || match injector.as_mut() {
+                Some(injector) => injector(name),
+                None => Language::find(name).map(Injection::Language),
+            })
+        });
+
+        FusedEvents {
+            captures, source, events, flag, deadline, language, injections,
+            offset: 0,
+            count: CANCELLATION_CHECK_INTERVAL,
+            done: false,
+        }
+    }
+
+    /// Highlights `source` as contiguous [`Span`]s, each with its stack of
+    /// highlight groups from outermost to innermost.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jellybean::{Language, COMMON_CAPTURES};
+    ///
+    /// let mut highlighter = Language::rust.custom_highlighter(COMMON_CAPTURES).unwrap();
+    /// let source = r#"println!("a\n");"#;
+    /// for span in highlighter.highlight_spans(source) {
+    ///     let span = span.unwrap();
+    ///     let names: Vec<&str> = span.groups.iter().map(|g| g.name).collect();
+    ///     if span.text == "\\n" {
+    ///         assert_eq!(names, ["string", "escape"]);
+    ///     }
+    /// }
+    /// ```
+    pub fn highlight_spans<'a>(
+        &'a mut self,
+        source: &'a str,
+    ) -> impl Iterator<Item = Result<Span<'a>>> + 'a {
+        Spans::new(self.highlight(source))
+    }
+
+    /// Highlights `source` as [`Line`]s, split as by [`str::lines()`], of
+    /// [`Span`]s that never cross line boundaries.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jellybean::{Language, COMMON_CAPTURES};
+    ///
+    /// let mut highlighter = Language::rust.custom_highlighter(COMMON_CAPTURES).unwrap();
+    /// let source = "/* a\nb */\nfn main() {}\n";
+    /// let lines = highlighter.highlight_lines(source)
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(lines.len(), 3);
+    /// assert_eq!(lines[0].spans[0].text, "/* a");
+    /// assert_eq!(lines[1].spans[0].text, "b */");
+    /// assert_eq!(lines[1].spans[0].group().unwrap().name, "comment");
+    /// ```
+    pub fn highlight_lines<'a>(
+        &'a mut self,
+        source: &'a str,
+    ) -> impl Iterator<Item = Result<Line<'a>>> + 'a {
+        Lines::new(Spans::new(self.highlight(source)))
+    }
+
+    /// Like [`Highlighter::highlight()`] but only emits balanced events for
+    /// the byte range `range`, widened to character boundaries.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jellybean::{Language, Highlight, COMMON_CAPTURES};
+    ///
+    /// let mut highlighter = Language::rust.custom_highlighter(COMMON_CAPTURES).unwrap();
+    /// let text: String = highlighter.highlight_range("let s = \"hello\";", 11..13)
+    ///     .filter_map(|event| match event.unwrap() {
+    ///         Highlight::Source { text, .. } => Some(text),
+    ///         _ => None,
+    ///     })
+    ///     .collect();
+    ///
+    /// assert_eq!(text, "ll");
+    /// ```
+    pub fn highlight_range<'a>(
+        &'a mut self,
+        source: &'a str,
+        range: Range<usize>,
+    ) -> impl Iterator<Item = Result<Highlight<'a>>> + 'a {
+        let mut start = range.start.min(source.len());
+        let mut end = range.end.min(source.len()).max(start);
+        while !source.is_char_boundary(start) { start -= 1; }
+        while !source.is_char_boundary(end) { end += 1; }
+
+        Windowed {
+            events: self.highlight(source),
+            window: start..end,
+            stack: vec![],
+            pending: VecDeque::new(),
+            done: false,
+        }
+    }
+
+    /// Like [`Highlighter::highlight_range()`] for the zero-indexed lines in
+    /// `lines`, including their trailing newlines.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jellybean::{Language, Highlight, COMMON_CAPTURES};
+    ///
+    /// let mut highlighter = Language::rust.custom_highlighter(COMMON_CAPTURES).unwrap();
+    /// let source = "fn a() {}\nfn b() {}\nfn c() {}\n";
+    /// let text: String = highlighter.highlight_line_range(source, 1..2)
+    ///     .filter_map(|event| match event.unwrap() {
+    ///         Highlight::Source { text, .. } => Some(text),
+    ///         _ => None,
+    ///     })
+    ///     .collect();
+    ///
+    /// assert_eq!(text, "fn b() {}\n");
+    /// ```
+    pub fn highlight_line_range<'a>(
+        &'a mut self,
+        source: &'a str,
+        lines: Range<usize>,
+    ) -> impl Iterator<Item = Result<Highlight<'a>>> + 'a {
+        let range = crate::util::line_byte_range(source, lines);
+        self.highlight_range(source, range)
+    }
+}
+
+impl InjectionCache {
+    /// Returns the configuration for the injection `name`, calling `resolver`
+    /// if `name` hasn't been resolved before. Errors are recorded in `failed`.
+    fn resolve<F>(
+        &self,
+        name: &str,
+        captures: &Captures,
+        resolver: F,
+    ) -> Option<&HighlightConfiguration>
+        where F: FnOnce() -> Option<Injection>
+    {
+        let mut slot = &self.head;
+        let entry = loop {
+            match slot.get() {
+                Some(entry) if entry.name == name => break entry,
+                Some(entry) => slot = &entry.next,
+                None => {
+                    let config = resolver().map(|injection| match injection {
+                        Injection::Config(config) => Ok(Source::Custom(config)),
+                        Injection::Language(language) => captures.injection_config(language),
+                    });
+
+                    let config = config.transpose();
+                    let entry = InjectionEntry { name: name.into(), config, next: OnceCell::new() };
+                    break slot.get_or_init(|| Box::new(entry));
+                }
             }
+        };
 
-            #[cfg(not(feature = "precached"))] None
-        });
+        let fail = |message: String| {
+            self.failed.set(Some(Error::InvalidInjection { name: name.into(), message }));
+            None
+        };
 
-        FusedEvents { captures, source, events, done: false }
+        let config: &HighlightConfiguration = match &entry.config {
+            Ok(config) => match config.as_ref()? {
+                Source::Custom(config) => &**config,
+                Source::Cached(config) => *config,
+            },
+            Err(e) => return fail(e.to_string()),
+        };
+
+        let version = config.language.version();
+        if !(MIN_COMPATIBLE_LANGUAGE_VERSION..=LANGUAGE_VERSION).contains(&version) {
+            return fail(format!("incompatible tree-sitter language version {version}"));
+        }
+
+        Some(config)
+    }
+}
+
+impl<I> FusedEvents<'_, I> {
+    fn error(&self, error: &tree_sitter_highlight::Error) -> Error {
+        use tree_sitter_highlight::Error as TsError;
+
+        match error {
+            TsError::Cancelled => Error::Cancelled { offset: self.offset },
+            // Injected languages are checked when they're resolved.
+            TsError::InvalidLanguage => Error::InvalidLanguage { language: self.language.into() },
+            TsError::Unknown => Error::Unknown,
+        }
+    }
+
+    fn cancelled(&mut self) -> bool {
+        if self.flag.is_none() && self.deadline.is_none() {
+            return false;
+        }
+
+        self.count += 1;
+        if self.count < CANCELLATION_CHECK_INTERVAL {
+            return false;
+        }
+
+        self.count = 0;
+        self.flag.is_some_and(|flag| flag.load(Ordering::Relaxed) != 0)
+            || self.deadline.is_some_and(|deadline| Instant::now() >= deadline)
     }
 }
 
-impl<'a, I> Iterator for FusedEvents<'a, Result<I>>
-    where I: Iterator<Item = Result<HighlightEvent>> + 'a
+impl<'a, I> Iterator for FusedEvents<'a, TsResult<I>>
+    where I: Iterator<Item = TsResult<HighlightEvent>> + 'a
 {
     type Item = Result<Highlight<'a>>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        use tree_sitter_highlight::Error;
-
         if self.done {
             return None;
         }
 
-        match self.events {
-            Ok(ref mut v) => v.next().map(|e| e.map(|v| match v {
-                HighlightEvent::Source { start, end } => Highlight::Source {
-                    text: &self.source[start..end],
-                    start, end
-                },
-                HighlightEvent::HighlightStart(h) => Highlight::Start {
-                    group: &self.captures.get(h.0).expect("have capture"),
-                    index: h.0,
-                },
-                HighlightEvent::HighlightEnd => Highlight::End,
-            })),
+        if self.cancelled() {
+            self.done = true;
+            return Some(Err(Error::Cancelled { offset: self.offset }));
+        }
+
+        let event = match self.events {
+            Ok(ref mut events) => events.next(),
             Err(ref e) => {
                 self.done = true;
-                Some(Err(match e {
-                    Error::Cancelled => Error::Cancelled,
-                    Error::InvalidLanguage => Error::InvalidLanguage,
-                    Error::Unknown => Error::Unknown,
-                }))
+                return Some(Err(self.error(e)));
+            }
+        };
+
+        // Injections are resolved while the next event is produced.
+        if let Some(error) = self.injections.failed.take() {
+            self.done = true;
+            return Some(Err(error));
+        }
+
+        Some(match event? {
+            Ok(HighlightEvent::Source { start, end }) => {
+                self.offset = end;
+                Ok(Highlight::Source { text: &self.source[start..end], start, end })
+            }
+            Ok(HighlightEvent::HighlightStart(h)) => match self.captures.get(h.0) {
+                Some(group) => Ok(Highlight::Start { group, index: h.0 }),
+                None => {
+                    self.done = true;
+                    Err(Error::UnknownCapture { index: h.0 })
+                }
+            },
+            Ok(HighlightEvent::HighlightEnd) => Ok(Highlight::End),
+            Err(e) => {
+                self.done = true;
+                Err(self.error(&e))
+            }
+        })
+    }
+}
+
+impl<'a, I> Windowed<'a, I> {
+    /// Queues an `End` for every emitted highlight and stops iteration.
+    fn finish(&mut self) {
+        let emitted = self.stack.drain(..).filter(|(.., emitted)| *emitted).count();
+        self.pending.extend(std::iter::repeat_with(|| Highlight::End).take(emitted));
+        self.done = true;
+    }
+}
+
+impl<'a, I> Iterator for Windowed<'a, I>
+    where I: Iterator<Item = Result<Highlight<'a>>>
+{
+    type Item = Result<Highlight<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(Ok(event));
+            }
+
+            if self.done {
+                return None;
+            }
+
+            match self.events.next() {
+                Some(Ok(Highlight::Start { group, index })) => {
+                    self.stack.push((group, index, false));
+                }
+                Some(Ok(Highlight::End)) => {
+                    if let Some((.., true)) = self.stack.pop() {
+                        return Some(Ok(Highlight::End));
+                    }
+                }
+                Some(Ok(Highlight::Source { text, start, end })) => {
+                    if end <= self.window.start {
+                        continue;
+                    }
+
+                    if start >= self.window.end {
+                        self.finish();
+                        continue;
+                    }
+
+                    // Open every highlight that hasn't been opened in the window.
+                    for (group, index, emitted) in self.stack.iter_mut() {
+                        if !*emitted {
+                            *emitted = true;
+                            self.pending.push_back(Highlight::Start { group: *group, index: *index });
+                        }
+                    }
+
+                    let clip = start.max(self.window.start)..end.min(self.window.end);
+                    self.pending.push_back(Highlight::Source {
+                        text: &text[(clip.start - start)..(clip.end - start)],
+                        start: clip.start,
+                        end: clip.end,
+                    });
+                }
+                Some(Err(e)) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+                None => self.finish(),
             }
         }
     }
@@ -115,25 +581,29 @@ impl<'a, I> Iterator for FusedEvents<'a, Result<I>>
 
 #[cfg(feature = "serde")]
 mod serde_impl {
-    use tree_sitter::SerializationError;
     use tree_sitter_highlight::{HighlightConfiguration, SerializableHighlightConfig};
-    use serde::{Serialize, Serializer, Deserialize, Deserializer, de::Error};
+    use serde::{Serialize, Serializer, Deserialize, Deserializer, de::Error as _};
 
     use super::*;
 
     type SerializationData = (Captures, SerializableHighlightConfig);
 
     impl Highlighter {
-        pub fn serializable(self) -> Result<impl Serialize, SerializationError> {
+        pub fn serializable(self) -> Result<impl Serialize> {
             let config = match self.config {
                 Source::Custom(config) => config,
                 #[cfg(feature = "precached")]
-                Source::Cached(_) => crate::dumps::fetch_config(self.language),
+                Source::Cached(_) => crate::dumps::fetch_config(self.language)?,
                 #[cfg(not(feature = "precached"))]
                 Source::Cached(_) => unreachable!(),
             };
 
-            Ok((self.captures, config.serializable()?) as SerializationData)
+            let config = config.serializable().map_err(|e| Error::Serialize {
+                language: self.language.name.into(),
+                message: format!("{e:?}"),
+            })?;
+
+            Ok((self.captures, config) as SerializationData)
         }
     }
 
@@ -144,7 +614,7 @@ mod serde_impl {
             let language = Language::find_by_name(language_name)
                 .ok_or_else(|| <D::Error>::custom("missing language"))?;
 
-            let config = HighlightConfiguration::deserialize(config, (language.language)())
+            let config = HighlightConfiguration::deserialize(config, language.raw())
                 .map_err(|e| <D::Error>::custom(format!("{e:?}")))?;
 
             Ok(Highlighter::new(language, config, captures))
@@ -197,6 +667,45 @@ impl Captures {
             Self::Cached(v) => v.get(i).copied(),
         }
     }
+
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = &str> + '_ {
+        let len = match self {
+            Self::Custom(v) => v.len(),
+            Self::Cached(v) => v.len(),
+        };
+
+        (0..len).map(|i| self.get(i).expect("in-bounds capture"))
+    }
+
+    /// Builds the highlight configuration for `language` recognizing `self`.
+    pub fn highlight_config(&self, language: &Language) -> Result<HighlightConfiguration> {
+        match self {
+            Self::Custom(v) => language.highlight_config(v),
+            Self::Cached(v) => language.highlight_config(v),
+        }
+    }
+
+    /// Returns a configuration for the injected `language` recognizing `self`,
+    /// reusing the precached configuration if it recognizes the same captures
+    /// or else one built previously by any highlighter.
+    fn injection_config(&self, language: &'static Language) -> Result<InjectionConfig> {
+        #[cfg(feature = "precached")]
+        if self.iter().eq(crate::EXHAUSTIVE_CAPTURES.iter().copied()) {
+            if let Some(config) = crate::dumps::cached_config(language) {
+                return config.map(Source::Cached);
+            }
+        }
+
+        let key = (language.name, self.iter().map(String::from).collect());
+        let mut configs = INJECTION_CONFIGS.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(config) = configs.get(&key) {
+            return Ok(Source::Custom(config.clone()));
+        }
+
+        let config = Arc::new(self.highlight_config(language)?);
+        configs.insert(key, config.clone());
+        Ok(Source::Custom(config))
+    }
 }
 
 impl<A> From<A> for Source<A, &'static A> {
@@ -1,11 +1,23 @@
 // use tree_sitter_highlight::HighlightConfiguration;
 // use ref_cast::{ref_cast_custom, RefCastCustom};
 
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ops::Range;
+
 use uncased::{UncasedStr, AsUncased};
+use tree_sitter::QueryError;
 use tree_sitter_highlight::HighlightConfiguration;
 
-use crate::{ALL_LANGUAGES, Highlighter};
+use crate::{ALL_LANGUAGES, Highlighter, Error, Diagnostic, Fold};
+use crate::tags::Tagger;
+use crate::config::ConfigBuilder;
 use crate::util::cmp_ignore_case_ascii;
+use crate::capture::{normalize_capture_with, normalize_query_with_rewrites, original_offset};
+
+/// A query's name, its text, and where text appended to the bundled query
+/// starts, if any was.
+pub(crate) type QuerySource<'a> = (&'static str, &'a str, Option<usize>);
 
 /// A materialized tree-sitter language.
 pub struct Language {
@@ -23,14 +35,60 @@ pub struct Language {
     /// See [supported languages](crate#supported-languages) for a full list.
     pub(crate) file_types: &'static [&'static UncasedStr],
 
-    /// The tree-sitter language function.
-    pub(crate) language: fn() -> tree_sitter::Language,
+    /// The tree-sitter language.
+    pub(crate) language: Raw,
 
     /// A list of tree-sitter queries (name, query data).
     pub(crate) queries: &'static [(&'static str, &'static str)],
 
+    /// Metadata about the language and its grammar.
+    pub(crate) info: Info,
+
+    /// The index of the precached configuration, if any. Languages created
+    /// at runtime have none.
     #[cfg(feature = "precached")]
-    pub(crate) dump_id: usize,
+    pub(crate) dump_id: Option<usize>,
+}
+
+/// Descriptive metadata about a [`Language`]. Empty strings are unknown.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Info {
+    pub(crate) display_name: &'static str,
+    pub(crate) description: &'static str,
+    pub(crate) license: &'static str,
+    pub(crate) repository: &'static str,
+    pub(crate) revision: &'static str,
+    pub(crate) line_comments: &'static [&'static str],
+    pub(crate) block_comment: Option<(&'static str, &'static str)>,
+    pub(crate) brackets: &'static [(&'static str, &'static str)],
+    pub(crate) indent: &'static str,
+}
+
+impl Info {
+    /// Metadata for a language created at runtime named `name`.
+    pub(crate) const fn runtime(name: &'static str) -> Info {
+        Info {
+            display_name: name,
+            description: "",
+            license: "",
+            repository: "",
+            revision: "",
+            line_comments: &[],
+            block_comment: None,
+            brackets: &[],
+            indent: "    ",
+        }
+    }
+}
+
+/// The source of a [`Language`]'s raw tree-sitter language.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Raw {
+    /// A grammar linked into the binary.
+    Linked(fn() -> tree_sitter::Language),
+    /// A grammar loaded at runtime.
+    #[cfg_attr(not(feature = "dynamic"), allow(dead_code))]
+    Loaded(tree_sitter::Language),
 }
 
 impl Language {
@@ -56,6 +114,70 @@ impl Language {
         self.name
     }
 
+    /// Returns the human-friendly name of the language, such as `"C#"` for
+    /// the language named `c_sharp`.
+    ///
+    /// For languages created at runtime, this is the language's name.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jellybean::Language;
+    ///
+    /// assert_eq!(Language::c_sharp.display_name(), "C#");
+    /// assert_eq!(Language::rust.display_name(), "Rust");
+    /// ```
+    pub fn display_name(&self) -> &str {
+        self.info.display_name
+    }
+
+    /// Returns the description of the grammar from its `package.json`, if it
+    /// has one.
+    pub fn description(&self) -> Option<&str> {
+        Some(self.info.description).filter(|s| !s.is_empty())
+    }
+
+    /// Returns the SPDX license expression of the grammar, such as `"MIT"`,
+    /// from its `package.json`, if it declares one.
+    pub fn license(&self) -> Option<&str> {
+        Some(self.info.license).filter(|s| !s.is_empty())
+    }
+
+    /// Returns the URL of the upstream repository the grammar was fetched
+    /// from, if known.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jellybean::Language;
+    ///
+    /// if let (Some(url), Some(rev)) = (Language::rust.repository(), Language::rust.revision()) {
+    ///     println!("rust grammar: {url} at {rev}");
+    /// }
+    /// ```
+    pub fn repository(&self) -> Option<&str> {
+        Some(self.info.repository).filter(|s| !s.is_empty())
+    }
+
+    /// Returns the git revision of [`Language::repository()`] the grammar was
+    /// built from, if known.
+    pub fn revision(&self) -> Option<&str> {
+        Some(self.info.revision).filter(|s| !s.is_empty())
+    }
+
+    /// Returns the tree-sitter ABI version the grammar was generated for.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jellybean::{Language, tree_sitter};
+    ///
+    /// assert!(Language::rust.abi_version() <= tree_sitter::LANGUAGE_VERSION);
+    /// ```
+    pub fn abi_version(&self) -> usize {
+        self.raw().version()
+    }
+
     /// Returns an iterator over the pairs of `(query name, query data)`.
     ///
     /// This is the raw tree-sitter query data for the language. It is
@@ -95,7 +217,10 @@ impl Language {
     /// let language = Language::rust.raw();
     /// ```
     pub fn raw(&self) -> tree_sitter::Language {
-        (self.language)()
+        match self.language {
+            Raw::Linked(language) => language(),
+            Raw::Loaded(language) => language,
+        }
     }
 
     pub fn find_query(&self, name: &str) -> Option<&str> {
@@ -104,30 +229,153 @@ impl Language {
             .map(|(_, v)| *v)
     }
 
-    pub fn highlight_config(&self, highlights: &[&str]) -> HighlightConfiguration {
+    /// Builds a highlight configuration for this language recognizing
+    /// `captures`, with its queries' captures normalized onto the
+    /// [`STANDARD_CAPTURES`](crate::STANDARD_CAPTURES).
+    pub fn highlight_config(
+        &self,
+        captures: &[impl AsRef<str>],
+    ) -> Result<HighlightConfiguration, Error> {
+        let query = |name: &'static str| (name, self.find_query(name).unwrap_or(""), None);
+        let queries = [query("injections"), query("locals"), query("highlights")];
+        self.configure_queries(queries, &HashMap::new(), captures)
+    }
+
+    /// Builds a highlight configuration for this language from the given
+    /// `injections`, `locals`, and `highlights` queries, normalizing their
+    /// captures with `overrides`, recognizing `captures`.
+    pub(crate) fn configure_queries(
+        &self,
+        queries: [QuerySource<'_>; 3],
+        overrides: &HashMap<String, String>,
+        captures: &[impl AsRef<str>],
+    ) -> Result<HighlightConfiguration, Error> {
+        let normalized = queries.map(|(_, query, _)| normalize_query_with_rewrites(query, |capture| {
+            normalize_capture_with(capture, |c| overrides.get(c).map(|s| s.as_str()))
+        }));
+
+        let [injections, locals, highlights] = &normalized;
         let mut config = HighlightConfiguration::new(
-            (self.language)(),
+            self.raw(),
             self.name,
-            self.find_query("highlights").unwrap_or(""),
-            self.find_query("injections").unwrap_or(""),
-            self.find_query("locals").unwrap_or(""),
+            &highlights.0,
+            &injections.0,
+            &locals.0,
             true,
-        ).expect("all queries pre-tested");
+        ).map_err(|e| self.query_error(&queries, &normalized, e))?;
+
+        config.configure(captures);
+        Ok(config)
+    }
+
+    /// Returns a [`ConfigBuilder`] for this language's queries.
+    pub fn config_builder(self: &'static Self) -> ConfigBuilder {
+        ConfigBuilder::new(self)
+    }
+
+    /// Attributes `error`, which occurred compiling the concatenation of the
+    /// `normalized` `queries` as one query, to the original query that caused
+    /// it, with its position in the original query's text.
+    fn query_error(
+        &self,
+        queries: &[QuerySource<'_>],
+        normalized: &[(Cow<'_, str>, Vec<(Range<usize>, usize)>)],
+        error: QueryError,
+    ) -> Error {
+        // A query that fails alone is the culprit. Otherwise, each compiles
+        // alone, so locate the error by its offset into the concatenation.
+        let culprit = normalized.iter().enumerate().find_map(|(i, (query, _))| {
+            let error = tree_sitter::Query::new(self.raw(), query).err()?;
+            Some((i, error.offset, error))
+        });
+
+        let (i, offset, error) = culprit.unwrap_or_else(|| {
+            let mut end = 0;
+            let i = normalized.iter()
+                .position(|(query, _)| { end += query.len(); error.offset < end })
+                .unwrap_or(normalized.len() - 1);
+
+            let start = normalized[..i].iter().map(|(query, _)| query.len()).sum::<usize>();
+            (i, error.offset.saturating_sub(start), error)
+        });
+
+        let (name, query, appended) = queries[i];
+        let offset = original_offset(&normalized[i].1, offset).min(query.len());
+        let (query, offset) = match appended {
+            Some(start) if offset >= start => (&query[start..], offset - start),
+            _ => (query, offset),
+        };
+
+        let prefix = query.get(..offset).unwrap_or(query);
+        let row = prefix.matches('\n').count();
+        let column = prefix.len() - prefix.rfind('\n').map_or(0, |i| i + 1);
+        Error::query(self.name, name, QueryError { row, column, offset, ..error })
+    }
+
+    /// Parses `source` and returns its syntax errors, in source order.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jellybean::{Language, DiagnosticKind};
+    ///
+    /// let errors = Language::rust.parse_errors("fn main() { let x = 1 }").unwrap();
+    /// for error in &errors {
+    ///     match error.kind {
+    ///         DiagnosticKind::Missing(kind) => println!("expected {kind}"),
+    ///         DiagnosticKind::Error => println!("bad source: {:?}", error.range),
+    ///     }
+    /// }
+    ///
+    /// assert!(!errors.is_empty());
+    /// assert!(Language::rust.parse_errors("fn main() {}").unwrap().is_empty());
+    /// ```
+    pub fn parse_errors(&self, source: &str) -> Result<Vec<Diagnostic>, Error> {
+        Ok(crate::diagnostics::collect(&self.parse(source)?))
+    }
+
+    /// Parses `source` and returns its multi-line `@fold` captures, or its
+    /// bracketed regions if the language has no usable `folds` query, ordered
+    /// by start with enclosing folds first.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jellybean::Language;
+    ///
+    /// let source = "fn main() {\n    let v = [\n        1,\n    ];\n}\n";
+    /// let folds = Language::rust.folding_ranges(source).unwrap();
+    /// assert_eq!((folds[0].start_line, folds[0].end_line, folds[0].depth), (0, 4, 0));
+    /// assert_eq!((folds[1].start_line, folds[1].end_line, folds[1].depth), (1, 3, 1));
+    /// ```
+    pub fn folding_ranges(&self, source: &str) -> Result<Vec<Fold>, Error> {
+        Ok(crate::fold::collect(self, &self.parse(source)?, source))
+    }
+
+    /// Parses `source` with a fresh parser.
+    pub(crate) fn parse(&self, source: &str) -> Result<tree_sitter::Tree, Error> {
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(self.raw())
+            .map_err(|_| Error::InvalidLanguage { language: self.name.into() })?;
+
+        parser.parse(source, None).ok_or(Error::Unknown)
+    }
 
-        config.configure(highlights);
-        config
+    /// Returns a [`Tagger`] for this language. See [`Tagger::new()`].
+    pub fn tagger(self: &'static Self) -> Result<Tagger, Error> {
+        Tagger::new(self)
     }
 
     pub fn custom_highlighter(
         self: &'static Self,
         captures: &'static [&'static str]
-    ) -> Highlighter {
-        let config = self.highlight_config(captures);
-        Highlighter::new(self, config, captures)
+    ) -> Result<Highlighter, Error> {
+        let config = self.highlight_config(captures)?;
+        Ok(Highlighter::new(self, config, captures))
     }
 
     #[cfg(feature = "precached")]
-    pub fn highlighter(self: &'static Self) -> Highlighter {
+    pub fn highlighter(self: &'static Self) -> Result<Highlighter, Error> {
         crate::dumps::fetch_highlighter(self)
     }
 }
@@ -143,34 +391,55 @@ macro_rules! define_associated_const {
             pub const $m: Language = Language {
                 name: crate::raw::$m::NAME,
                 file_types: unsafe { std::mem::transmute(crate::raw::$m::FILE_TYPES) },
-                language: crate::raw::$m::language,
+                language: Raw::Linked(crate::raw::$m::language),
                 queries: crate::raw::$m::QUERIES,
+                info: Info {
+                    display_name: crate::raw::$m::DISPLAY_NAME,
+                    description: crate::raw::$m::DESCRIPTION,
+                    license: crate::raw::$m::LICENSE,
+                    repository: crate::raw::$m::REPOSITORY,
+                    revision: crate::raw::$m::REVISION,
+                    line_comments: crate::raw::$m::LINE_COMMENTS,
+                    block_comment: crate::raw::$m::BLOCK_COMMENT,
+                    brackets: crate::raw::$m::BRACKETS,
+                    indent: crate::raw::$m::INDENT,
+                },
                 #[cfg(feature = "precached")]
-                dump_id: crate::dumps::$m,
+                dump_id: Some(crate::dumps::$m),
             };
         )*
     }
 }
 
 impl Language {
+    /// Finds a language by name, then by file type, then by
+    /// [alias](Language::aliases()).
     #[inline]
     pub fn find(token: &str) -> Option<&'static Language> {
-        Self::find_by_name(token).or_else(|| Self::find_by_file_type(token))
+        Self::find_by_name(token)
+            .or_else(|| Self::find_by_file_type(token))
+            .or_else(|| Self::find_by_alias(token))
     }
 
     #[inline]
     pub fn find_by_name(name: &str) -> Option<&'static Language> {
-        Self::position_by_name(name).and_then(|i| ALL_LANGUAGES.get(i).copied())
+        Self::position_by_name(name)
+            .and_then(|i| ALL_LANGUAGES.get(i).copied())
+            .or_else(|| crate::registry::find_by_name(name))
     }
 
     #[inline]
     pub fn find_by_file_type(file_type: &str) -> Option<&'static Language> {
-        Self::position_by_file_type(file_type).and_then(|i| ALL_LANGUAGES.get(i).copied())
+        Self::position_by_file_type(file_type)
+            .and_then(|i| ALL_LANGUAGES.get(i).copied())
+            .or_else(|| crate::registry::find_by_file_type(file_type))
     }
 
     #[inline]
     pub fn position(token: &str) -> Option<usize> {
-        Self::position_by_name(token).or_else(|| Self::position_by_file_type(token))
+        Self::position_by_name(token)
+            .or_else(|| Self::position_by_file_type(token))
+            .or_else(|| Self::position_by_name(Self::find_by_alias(token)?.name))
     }
 
     #[inline]
@@ -197,6 +466,7 @@ impl std::fmt::Debug for Language {
             .field("file_types", &self.file_types)
             .field("language", &self.language)
             .field("queries", &self.queries)
+            .field("info", &self.info)
             .finish()
     }
 }
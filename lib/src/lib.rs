@@ -9,8 +9,9 @@
 #![recursion_limit = "512"]
 
 // This expands (from build.rs) to the `raw` module, a `docs` macro which calls
-// the `doc!()` macro declred here with each language (used above), and a
-// `with_all_languages` higher-order macro, used in `language.rs`.
+// the `doc!()` macro declred here with each language (used above), and the
+// `with_all_languages` and `with_standard_captures` higher-order macros, used
+// in `language.rs` and `taxonomy.rs`.
 include!(concat!(env!("OUT_DIR"), "/codegen.rs"));
 
 mod doc;
@@ -18,7 +19,24 @@ mod language;
 mod util;
 mod highlighter;
 mod capture;
+mod taxonomy;
 mod theme;
+mod error;
+mod span;
+mod text;
+mod diagnostics;
+mod fold;
+mod config;
+mod registry;
+mod detect;
+mod guess;
+mod aliases;
+mod editor;
+
+#[cfg(feature = "dynamic")]
+mod load;
+
+pub mod tags;
 
 #[cfg(feature = "precached")]
 pub(crate) mod dumps;
@@ -27,9 +45,19 @@ pub use tree_sitter;
 pub use tree_sitter_highlight;
 
 pub use language::Language;
-pub use highlighter::{Highlighter, Highlight};
-pub use theme::Theme;
+pub use highlighter::{Highlighter, Highlight, Injection};
+pub use theme::{Theme, CompiledTheme};
+pub use config::ConfigBuilder;
+
+#[cfg(feature = "dynamic")]
+pub use load::Loader;
+pub use error::Error;
+pub use span::{Group, Span, Line};
+pub use text::HighlightedText;
+pub use diagnostics::{Diagnostic, DiagnosticKind};
+pub use fold::Fold;
 pub use capture::*;
+pub use taxonomy::Capture;
 
 macro_rules! collect {
     ($($m:ident),*) => (
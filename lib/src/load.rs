@@ -0,0 +1,232 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use uncased::UncasedStr;
+
+use crate::{Language, Error};
+use crate::language::{Info, Raw};
+
+/// The maximum depth of `; inherits:` chains between query files.
+const MAX_INHERITS_DEPTH: usize = 16;
+
+/// Loads a [`Language`] at runtime from a compiled grammar and a directory of
+/// queries.
+///
+/// The grammar is a shared library exporting the `tree_sitter_{name}`
+/// function, with `-` in the name replaced by `_`, as built by the
+/// tree-sitter CLI. The queries are the `.scm` files in the queries
+/// directory, named by their file stem, which must include `highlights.scm`.
+/// As in Helix, a query file may begin with `; inherits: a,b` to include the
+/// query of the same name from the sibling directories `a` and `b`.
+///
+/// The loaded language is leaked and registered: it's found by
+/// [`Language::find()`], [`Language::find_by_name()`], and
+/// [`Language::find_by_file_type()`] and is resolved for injections. The
+/// library is never unloaded.
+///
+/// Requires the `dynamic` feature.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use jellybean::{Language, Loader, COMMON_CAPTURES};
+///
+/// // SAFETY: `runtime/grammars/foo.so` is a tree-sitter grammar.
+/// let foo = unsafe {
+///     Loader::new("foo", "runtime/grammars/foo.so")
+///         .queries("runtime/queries/foo")
+///         .file_types(["foo"])
+///         .load()
+///         .unwrap()
+/// };
+///
+/// assert_eq!(Language::find("foo").unwrap().name(), "foo");
+/// let mut highlighter = foo.custom_highlighter(COMMON_CAPTURES).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Loader {
+    name: String,
+    library: PathBuf,
+    queries: Option<PathBuf>,
+    file_types: Vec<String>,
+}
+
+impl Loader {
+    /// Creates a loader for the language `name` whose grammar is the shared
+    /// library at `library` and whose queries are, by default, in the sibling
+    /// directory `../queries/{name}`.
+    pub fn new(name: impl Into<String>, library: impl Into<PathBuf>) -> Self {
+        Loader { name: name.into(), library: library.into(), queries: None, file_types: vec![] }
+    }
+
+    /// Creates a loader for the language `name` in the Helix-style runtime
+    /// directory `runtime`: the grammar is `grammars/{name}.{so,dylib,dll}`
+    /// and the queries are in `queries/{name}`.
+    pub fn runtime(runtime: impl AsRef<Path>, name: impl Into<String>) -> Self {
+        let (runtime, name) = (runtime.as_ref(), name.into());
+        let library = runtime.join("grammars")
+            .join(&name)
+            .with_extension(std::env::consts::DLL_EXTENSION);
+
+        Loader::new(name.clone(), library).queries(runtime.join("queries").join(name))
+    }
+
+    /// Reads queries from the directory `dir`.
+    pub fn queries(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.queries = Some(dir.into());
+        self
+    }
+
+    /// Sets the file types, i.e, extensions or file names, that identify the
+    /// language.
+    pub fn file_types<I, S>(mut self, file_types: I) -> Self
+        where I: IntoIterator<Item = S>, S: Into<String>
+    {
+        self.file_types = file_types.into_iter().map(|s| s.into()).collect();
+        self
+    }
+
+    /// Loads the grammar and queries and registers the language.
+    ///
+    /// # Safety
+    ///
+    /// Loading a shared library runs its initialization routines, and the
+    /// library's `tree_sitter_{name}` symbol is called as a function
+    /// returning a tree-sitter language. The library must be a tree-sitter
+    /// grammar for both to be sound.
+    pub unsafe fn load(self) -> Result<&'static Language, Error> {
+        let load_error = |path: &Path, e: &dyn std::fmt::Display| Error::Load {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        };
+
+        // Nothing is read or leaked for a language that can't be registered.
+        crate::registry::check_available(&self.name)?;
+
+        let queries_dir = match self.queries {
+            Some(dir) => dir,
+            None => self.library.parent()
+                .and_then(|grammars| grammars.parent())
+                .unwrap_or(Path::new(""))
+                .join("queries")
+                .join(&self.name),
+        };
+
+        let queries = read_queries(&queries_dir).map_err(|(path, e)| load_error(&path, &e))?;
+        if !queries.contains_key("highlights") {
+            return Err(Error::MissingQuery { language: self.name, query: "highlights" });
+        }
+
+        let library = libloading::Library::new(&self.library)
+            .map_err(|e| load_error(&self.library, &e))?;
+
+        let symbol = format!("tree_sitter_{}", self.name.replace('-', "_"));
+        let raw = library.get::<unsafe extern "C" fn() -> tree_sitter::Language>(symbol.as_bytes())
+            .map(|function| function())
+            .map_err(|e| load_error(&self.library, &e))?;
+
+        if tree_sitter::Parser::new().set_language(raw).is_err() {
+            return Err(Error::InvalidLanguage { language: self.name });
+        }
+
+        // The language points into the library, so it must never be unloaded.
+        std::mem::forget(library);
+
+        let mut file_types = self.file_types;
+        file_types.sort_by(|a, b| UncasedStr::new(a).cmp(UncasedStr::new(b)));
+        file_types.dedup_by(|a, b| UncasedStr::new(a) == UncasedStr::new(b));
+        let file_types = file_types.into_iter()
+            .map(|ft| UncasedStr::new(Box::leak(ft.into_boxed_str())))
+            .collect::<Vec<&'static UncasedStr>>();
+
+        let queries = queries.into_iter()
+            .map(|(name, query)| (leak(name), leak(query)))
+            .collect::<Vec<_>>();
+
+        let name = leak(self.name);
+        crate::registry::register(Language {
+            name,
+            file_types: Box::leak(file_types.into_boxed_slice()),
+            language: Raw::Loaded(raw),
+            queries: Box::leak(queries.into_boxed_slice()),
+            info: Info::runtime(name),
+            #[cfg(feature = "precached")]
+            dump_id: None,
+        })
+    }
+}
+
+impl Language {
+    /// Loads the language `name` from a Helix-style runtime directory and
+    /// registers it. Shorthand for
+    /// [`Loader::runtime(runtime, name).load()`](Loader::load()).
+    ///
+    /// Requires the `dynamic` feature.
+    ///
+    /// # Safety
+    ///
+    /// See [`Loader::load()`].
+    pub unsafe fn load(runtime: impl AsRef<Path>, name: &str) -> Result<&'static Language, Error> {
+        Loader::runtime(runtime, name).load()
+    }
+}
+
+fn leak(string: String) -> &'static str {
+    Box::leak(string.into_boxed_str())
+}
+
+/// Reads the `.scm` queries in `dir`, resolving `; inherits:` directives.
+fn read_queries(dir: &Path) -> Result<BTreeMap<String, String>, (PathBuf, String)> {
+    let entries = dir.read_dir().map_err(|e| (dir.to_path_buf(), e.to_string()))?;
+    let mut queries = BTreeMap::new();
+    for entry in entries {
+        let path = entry.map_err(|e| (dir.to_path_buf(), e.to_string()))?.path();
+        if path.extension().is_none_or(|ext| ext != "scm") {
+            continue;
+        }
+
+        let name = match path.file_stem().and_then(|stem| stem.to_str()) {
+            Some(name) => name.to_string(),
+            None => continue,
+        };
+
+        let query = read_query(&path, 0)?;
+        queries.insert(name, query);
+    }
+
+    Ok(queries)
+}
+
+/// Reads the query at `path`, prepending the queries it inherits.
+fn read_query(path: &Path, depth: usize) -> Result<String, (PathBuf, String)> {
+    let query = std::fs::read_to_string(path).map_err(|e| (path.to_path_buf(), e.to_string()))?;
+    let inherits = query.lines()
+        .take_while(|line| line.starts_with(';'))
+        .find_map(|line| line.trim_start_matches(';').trim().strip_prefix("inherits:"));
+
+    let inherits = match inherits {
+        Some(inherits) => inherits,
+        None => return Ok(query),
+    };
+
+    if depth >= MAX_INHERITS_DEPTH {
+        return Err((path.to_path_buf(), "too many nested `inherits`".into()));
+    }
+
+    let (file_name, dir) = match (path.file_name(), path.parent().and_then(|p| p.parent())) {
+        (Some(file_name), Some(dir)) => (file_name, dir),
+        _ => return Ok(query),
+    };
+
+    let mut combined = String::new();
+    for language in inherits.split(',').map(|l| l.trim()).filter(|l| !l.is_empty()) {
+        let inherited = dir.join(language).join(file_name);
+        if inherited.exists() {
+            combined.push_str(&read_query(&inherited, depth + 1)?);
+            combined.push('\n');
+        }
+    }
+
+    combined.push_str(&query);
+    Ok(combined)
+}
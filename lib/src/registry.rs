@@ -0,0 +1,127 @@
+use std::sync::{RwLock, RwLockReadGuard};
+
+use uncased::{AsUncased, UncasedStr};
+
+use crate::{Language, Error};
+use crate::language::{Info, Raw};
+use crate::util::cmp_ignore_case_ascii;
+
+/// Languages created at runtime, in registration order.
+static REGISTRY: RwLock<Vec<&'static Language>> = RwLock::new(Vec::new());
+
+fn registry() -> RwLockReadGuard<'static, Vec<&'static Language>> {
+    REGISTRY.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Leaks `language` and adds it to the registry so that it's found by
+/// [`Language::find()`] and friends.
+///
+/// Fails if a bundled or registered language has the same name, ignoring
+/// case.
+pub(crate) fn register(language: Language) -> Result<&'static Language, Error> {
+    let mut registry = REGISTRY.write().unwrap_or_else(|e| e.into_inner());
+    if is_taken(&registry, language.name) {
+        return Err(Error::DuplicateLanguage { name: language.name.into() });
+    }
+
+    let language: &'static Language = Box::leak(Box::new(language));
+    registry.push(language);
+    Ok(language)
+}
+
+/// Returns an [`Error::DuplicateLanguage`] if a bundled or registered
+/// language is named `name`, ignoring case.
+///
+/// Callers that allocate or leak resources for a language check this first,
+/// though [`register()`] checks again.
+pub(crate) fn check_available(name: &str) -> Result<(), Error> {
+    if is_taken(&registry(), name) {
+        return Err(Error::DuplicateLanguage { name: name.into() });
+    }
+
+    Ok(())
+}
+
+fn is_taken(registry: &[&'static Language], name: &str) -> bool {
+    Language::position_by_name(name).is_some()
+        || registry.iter().any(|l| cmp_ignore_case_ascii(l.name, name).is_eq())
+}
+
+/// Finds a registered language by name, ignoring case.
+pub(crate) fn find_by_name(name: &str) -> Option<&'static Language> {
+    registry().iter()
+        .find(|l| cmp_ignore_case_ascii(l.name, name).is_eq())
+        .copied()
+}
+
+/// Finds the first registered language that recognizes `file_type`.
+pub(crate) fn find_by_file_type(file_type: &str) -> Option<&'static Language> {
+    registry().iter()
+        .find(|l| l.file_types.binary_search(&file_type.as_uncased()).is_ok())
+        .copied()
+}
+
+impl Language {
+    /// Registers a language that isn't bundled, named `name`, recognizing
+    /// `file_types`, and highlighted with `queries`, which must include a
+    /// `highlights` query. Registered languages are found after bundled ones.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jellybean::{Language, COMMON_CAPTURES};
+    ///
+    /// // Register the Rust grammar under another name.
+    /// let dsl = Language::register(
+    ///     "rusty-dsl",
+    ///     &["rdsl"],
+    ///     jellybean::raw::rust::language,
+    ///     &[("highlights", "(identifier) @variable")],
+    /// ).unwrap();
+    ///
+    /// assert_eq!(Language::find("rusty-dsl").unwrap().name(), "rusty-dsl");
+    /// assert_eq!(Language::find_by_file_type("rdsl").unwrap().name(), "rusty-dsl");
+    /// assert!(Language::registered().iter().any(|l| l.name() == dsl.name()));
+    ///
+    /// let mut highlighter = dsl.custom_highlighter(COMMON_CAPTURES).unwrap();
+    /// assert!(highlighter.highlight("let x = 1;").all(|event| event.is_ok()));
+    ///
+    /// assert!(Language::register("Rust", &[], jellybean::raw::rust::language, &[]).is_err());
+    /// ```
+    pub fn register(
+        name: &'static str,
+        file_types: &'static [&'static str],
+        language: fn() -> tree_sitter::Language,
+        queries: &'static [(&'static str, &'static str)],
+    ) -> Result<&'static Language, Error> {
+        if !queries.iter().any(|(name, _)| *name == "highlights") {
+            return Err(Error::MissingQuery { language: name.into(), query: "highlights" });
+        }
+
+        if tree_sitter::Parser::new().set_language(language()).is_err() {
+            return Err(Error::InvalidLanguage { language: name.into() });
+        }
+
+        let mut file_types = file_types.iter()
+            .map(|ft| UncasedStr::new(ft))
+            .collect::<Vec<&'static UncasedStr>>();
+
+        file_types.sort();
+        file_types.dedup();
+        register(Language {
+            name,
+            file_types: Box::leak(file_types.into_boxed_slice()),
+            language: Raw::Linked(language),
+            queries,
+            info: Info::runtime(name),
+            #[cfg(feature = "precached")]
+            dump_id: None,
+        })
+    }
+
+    /// Returns the languages registered via [`Language::register()`] or
+    /// loaded at runtime, in registration order.
+    pub fn registered() -> Vec<&'static Language> {
+        registry().to_vec()
+    }
+}
@@ -0,0 +1,193 @@
+use std::ops::Range;
+
+use crate::{Highlight, Error, Capture};
+
+/// A highlight group: the name of a capture and its index in the
+/// highlighter's [captures](crate::Highlighter::captures()).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Group<'a> {
+    /// The name of the capture.
+    pub name: &'a str,
+    /// The index of the capture.
+    pub index: usize,
+}
+
+/// A contiguous piece of source with a fixed stack of highlight groups.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span<'a> {
+    /// The text of the span.
+    pub text: &'a str,
+    /// The byte range of `text` in the source.
+    pub range: Range<usize>,
+    /// The active highlight groups, from outermost to innermost.
+    pub groups: Vec<Group<'a>>,
+}
+
+/// A single line of highlighted source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line<'a> {
+    /// The zero-based index of the line.
+    pub index: usize,
+    /// The byte range of the line in the source, excluding the terminator.
+    pub range: Range<usize>,
+    /// The spans that make up the line, in order. Spans never contain a line
+    /// terminator. Empty if the line is empty.
+    pub spans: Vec<Span<'a>>,
+}
+
+impl Group<'_> {
+    /// Returns the [`Capture`] named by this group, if it's in the standard
+    /// taxonomy.
+    pub fn capture(&self) -> Option<Capture> {
+        Capture::from_name(self.name)
+    }
+}
+
+impl<'a> Span<'a> {
+    /// Returns the innermost highlight group, if any.
+    pub fn group(&self) -> Option<Group<'a>> {
+        self.groups.last().copied()
+    }
+
+    /// Splits `self` into the part before `at`, relative to the start of
+    /// `self`, and the part after.
+    fn split_at(self, at: usize) -> (Span<'a>, Span<'a>) {
+        let (head, tail) = self.text.split_at(at);
+        let mid = self.range.start + at;
+        let head = Span { text: head, range: self.range.start..mid, groups: self.groups.clone() };
+        let tail = Span { text: tail, range: mid..self.range.end, groups: self.groups };
+        (head, tail)
+    }
+}
+
+/// Iterator of spans from an iterator of highlight events.
+pub(crate) struct Spans<'a, I> {
+    events: I,
+    stack: Vec<Group<'a>>,
+    done: bool,
+}
+
+/// Iterator of lines from an iterator of spans.
+pub(crate) struct Lines<'a, I> {
+    spans: I,
+    line: Line<'a>,
+    /// The remainder of a span that crossed a line boundary.
+    pending: Option<Span<'a>>,
+    done: bool,
+}
+
+impl<'a, I> Spans<'a, I> {
+    pub fn new(events: I) -> Self {
+        Spans { events, stack: vec![], done: false }
+    }
+}
+
+impl<'a, I> Iterator for Spans<'a, I>
+    where I: Iterator<Item = Result<Highlight<'a>, Error>>
+{
+    type Item = Result<Span<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            match self.events.next()? {
+                Ok(Highlight::Start { group, index }) => {
+                    self.stack.push(Group { name: group, index });
+                }
+                Ok(Highlight::End) => {
+                    self.stack.pop();
+                }
+                Ok(Highlight::Source { text, start, end }) => {
+                    if start == end {
+                        continue;
+                    }
+
+                    let groups = self.stack.clone();
+                    return Some(Ok(Span { text, range: start..end, groups }));
+                }
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl<'a, I> Lines<'a, I> {
+    pub fn new(spans: I) -> Self {
+        let line = Line { index: 0, range: 0..0, spans: vec![] };
+        Lines { spans, line, pending: None, done: false }
+    }
+}
+
+impl<'a, I> Iterator for Lines<'a, I>
+    where I: Iterator<Item = Result<Span<'a>, Error>>
+{
+    type Item = Result<Line<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        loop {
+            let span = match self.pending.take().map(Ok).or_else(|| self.spans.next()) {
+                Some(Ok(span)) => span,
+                Some(Err(e)) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+                None => {
+                    self.done = true;
+                    let line = std::mem::replace(&mut self.line, Line {
+                        index: 0, range: 0..0, spans: vec![]
+                    });
+
+                    return (!line.range.is_empty()).then_some(Ok(line));
+                }
+            };
+
+            let Some(newline) = span.text.find('\n') else {
+                self.line.range.end = span.range.end;
+                self.line.spans.push(span);
+                continue;
+            };
+
+            let next_start = span.range.start + newline + 1;
+            let (head, tail) = span.split_at(newline);
+            let (_, tail) = tail.split_at(1);
+            if !tail.text.is_empty() {
+                self.pending = Some(tail);
+            }
+
+            self.line.range.end = head.range.end;
+            if !head.text.is_empty() {
+                self.line.spans.push(head);
+            }
+
+            // The `\r` of a `\r\n` can end a span before the one with the `\n`.
+            if let Some(last) = self.line.spans.pop() {
+                let last = if last.text.ends_with('\r') {
+                    last.split_at(last.text.len() - 1).0
+                } else {
+                    last
+                };
+
+                self.line.range.end = last.range.end;
+                if !last.text.is_empty() {
+                    self.line.spans.push(last);
+                }
+            }
+
+            let next = Line {
+                index: self.line.index + 1,
+                range: next_start..next_start,
+                spans: vec![],
+            };
+
+            return Some(Ok(std::mem::replace(&mut self.line, next)));
+        }
+    }
+}
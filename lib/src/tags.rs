@@ -0,0 +1,247 @@
+//! Code navigation tags: definitions of and references to symbols.
+//!
+//! Tags are extracted using a language's `tags` query, which follows the
+//! [tree-sitter tagging conventions]: a `@name` capture for the symbol's
+//! name, a `@definition.{kind}` or `@reference.{kind}` capture for the
+//! tagged syntax node, and an optional `@doc` capture for documentation,
+//! cleaned up via the `#strip!` and `#select-adjacent!` predicates.
+//!
+//! [tree-sitter tagging conventions]: https://tree-sitter.github.io/tree-sitter/code-navigation-systems
+//!
+//! # Example
+//!
+//! ```rust
+//! use jellybean::Language;
+//! use jellybean::tags::Role;
+//!
+//! let tagger = Language::rust.tagger().unwrap();
+//! let source = "/// Says hi.\nfn hello() {}\n\nfn main() { hello(); }";
+//! let tags = tagger.tags(source).unwrap();
+//!
+//! let hello = tags.iter().find(|t| t.name == "hello").unwrap();
+//! assert_eq!(hello.role, Role::Definition);
+//! assert_eq!(hello.kind, "function");
+//! assert_eq!(hello.start.row, 1);
+//!
+//! assert!(tags.iter().any(|t| t.name == "hello" && t.role == Role::Reference));
+//! ```
+
+use std::collections::HashSet;
+use std::ops::Range;
+
+use regex::Regex;
+use tree_sitter::{Node, Point, Query, QueryCursor, QueryPredicateArg};
+
+use crate::{Language, Error};
+
+/// Extracts [`Tag`]s from source in a given language.
+///
+/// Create one via [`Language::tagger()`] or [`Tagger::new()`].
+pub struct Tagger {
+    language: &'static Language,
+    query: Query,
+    /// The role of each capture in `query` that tags a syntax node.
+    roles: Vec<Option<Role>>,
+    /// The index of the `@name` capture.
+    name: Option<u32>,
+    /// The index of the `@doc` capture.
+    doc: Option<u32>,
+    /// Predicates of each pattern in `query`.
+    patterns: Vec<Pattern>,
+}
+
+#[derive(Default)]
+struct Pattern {
+    /// Regex matching text to remove from docs: `#strip! @doc "regex"`.
+    strip: Option<Regex>,
+    /// Only docs adjacent to this capture are kept: `#select-adjacent! @doc @capture`.
+    adjacent: Option<u32>,
+}
+
+/// Whether a tag defines a symbol or refers to one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Role {
+    /// The tag defines the symbol. Captured as `@definition.{kind}`.
+    Definition,
+    /// The tag refers to the symbol. Captured as `@reference.{kind}`.
+    Reference,
+}
+
+/// A definition of or reference to a symbol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tag<'a> {
+    /// Whether this tag is a definition or a reference.
+    pub role: Role,
+    /// The kind of the symbol, e.g, `"function"`, `"class"`, or `"call"`.
+    pub kind: &'a str,
+    /// The name of the symbol.
+    pub name: &'a str,
+    /// The byte range of the entire tagged syntax node.
+    pub range: Range<usize>,
+    /// The byte range of `name`.
+    pub name_range: Range<usize>,
+    /// The zero-based row and byte column where `name` starts.
+    pub start: Point,
+    /// The documentation attached to the symbol, if any.
+    pub docs: Option<String>,
+}
+
+impl Tagger {
+    /// Creates a tagger for `language` using its bundled `tags` query.
+    ///
+    /// Returns an [`Error::MissingQuery`] if the language doesn't bundle a
+    /// `tags` query and an [`Error::Query`] if the query fails to compile.
+    pub fn new(language: &'static Language) -> Result<Self, Error> {
+        let source = language.find_query("tags")
+            .ok_or_else(|| Error::MissingQuery { language: language.name.into(), query: "tags" })?;
+
+        let query = Query::new(language.raw(), source)
+            .map_err(|e| Error::query(language.name, "tags", e))?;
+
+        let roles = query.capture_names().iter()
+            .map(|name| match name.split_once('.') {
+                Some(("definition", _)) => Some(Role::Definition),
+                Some(("reference", _)) => Some(Role::Reference),
+                _ => None,
+            })
+            .collect();
+
+        let patterns = (0..query.pattern_count())
+            .map(|i| {
+                let mut pattern = Pattern::default();
+                for predicate in query.general_predicates(i) {
+                    match (&*predicate.operator, &*predicate.args) {
+                        ("strip!", [_, QueryPredicateArg::String(regex)]) => {
+                            let regex = Regex::new(regex).map_err(|e| {
+                                let start = query.start_byte_for_pattern(i);
+                                let prefix = &source[..start];
+                                Error::Query {
+                                    language: language.name.into(),
+                                    query: "tags",
+                                    row: prefix.matches('\n').count(),
+                                    column: start - prefix.rfind('\n').map_or(0, |n| n + 1),
+                                    message: e.to_string(),
+                                }
+                            })?;
+
+                            pattern.strip = Some(regex);
+                        }
+                        ("select-adjacent!", [_, QueryPredicateArg::Capture(index)]) => {
+                            pattern.adjacent = Some(*index);
+                        }
+                        _ => {}
+                    }
+                }
+
+                Ok(pattern)
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(Tagger {
+            language,
+            name: query.capture_index_for_name("name"),
+            doc: query.capture_index_for_name("doc"),
+            roles,
+            patterns,
+            query,
+        })
+    }
+
+    /// The language this tagger extracts tags from.
+    pub fn language(&self) -> &'static Language {
+        self.language
+    }
+
+    /// Parses `source` and returns its tags, ordered by the position of their
+    /// name in `source`.
+    ///
+    /// Each symbol name is tagged at most once per role.
+    pub fn tags<'a>(&'a self, source: &'a str) -> Result<Vec<Tag<'a>>, Error> {
+        let tree = self.language.parse(source)?;
+        let mut cursor = QueryCursor::new();
+        let mut seen = HashSet::new();
+        let mut tags = vec![];
+        for m in cursor.matches(&self.query, tree.root_node(), source.as_bytes()) {
+            let (mut name, mut syntax, mut docs) = (None, None, vec![]);
+            for capture in m.captures {
+                if Some(capture.index) == self.name {
+                    name = Some(capture.node);
+                } else if Some(capture.index) == self.doc {
+                    docs.push(capture.node);
+                } else if let Some(role) = self.roles[capture.index as usize] {
+                    syntax = Some((role, capture.index as usize, capture.node));
+                }
+            }
+
+            let (name, (role, index, node)) = match (name, syntax) {
+                (Some(name), Some(syntax)) => (name, syntax),
+                _ => continue,
+            };
+
+            let name_range = name.byte_range();
+            let text = match source.get(name_range.clone()) {
+                Some(text) if seen.insert((name_range.clone(), role)) => text,
+                _ => continue,
+            };
+
+            let pattern = &self.patterns[m.pattern_index];
+            if let Some(adjacent) = pattern.adjacent {
+                if let Some(capture) = m.captures.iter().find(|c| c.index == adjacent) {
+                    docs = adjacent_docs(docs, capture.node);
+                }
+            }
+
+            let docs = (!docs.is_empty()).then(|| {
+                docs.iter()
+                    .filter_map(|doc| source.get(doc.byte_range()))
+                    .map(|text| match &pattern.strip {
+                        Some(regex) => regex.replace_all(text, "").into_owned(),
+                        None => text.to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            });
+
+            let kind = &self.query.capture_names()[index];
+            tags.push(Tag {
+                role,
+                kind: kind.split_once('.').map_or(&**kind, |(_, kind)| kind),
+                name: text,
+                range: node.byte_range(),
+                name_range,
+                start: name.start_position(),
+                docs,
+            });
+        }
+
+        tags.sort_by_key(|tag| tag.name_range.start);
+        Ok(tags)
+    }
+}
+
+/// Returns the trailing run of `docs` that immediately precedes `node`, with
+/// no blank lines in between.
+fn adjacent_docs<'t>(mut docs: Vec<Node<'t>>, node: Node<'t>) -> Vec<Node<'t>> {
+    docs.sort_by_key(|doc| doc.start_byte());
+    docs.retain(|doc| doc.end_byte() <= node.start_byte());
+
+    let mut row = node.start_position().row;
+    let adjacent = docs.iter()
+        .rev()
+        .take_while(|doc| {
+            let adjacent = doc.end_position().row + 1 >= row;
+            row = doc.start_position().row;
+            adjacent
+        })
+        .count();
+
+    docs.split_off(docs.len() - adjacent)
+}
+
+impl std::fmt::Debug for Tagger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Tagger")
+            .field("language", &self.language.name)
+            .finish_non_exhaustive()
+    }
+}
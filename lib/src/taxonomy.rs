@@ -0,0 +1,109 @@
+use crate::STANDARD_CAPTURES;
+
+macro_rules! define_standard_captures {
+    ($($variant:ident => $name:literal),* $(,)?) => {
+        /// A capture in the standard taxonomy, [`STANDARD_CAPTURES`].
+        ///
+        /// Captures form a hierarchy by their dotted names: the
+        /// [`parent()`](Capture::parent()) of `function.macro` is `function`.
+        /// Static [`Theme`]s are keyed by `Capture`, so a misspelled capture
+        /// is a compile-time error.
+        ///
+        /// [`Theme`]: crate::Theme
+        ///
+        /// # Example
+        ///
+        /// ```rust
+        /// use jellybean::Capture;
+        ///
+        /// assert_eq!(Capture::FunctionMacro.name(), "function.macro");
+        /// assert_eq!(Capture::FunctionMacro.parent(), Some(Capture::Function));
+        /// assert_eq!(Capture::from_name("function.macro"), Some(Capture::FunctionMacro));
+        /// assert_eq!(Capture::Function.parent(), None);
+        /// ```
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub enum Capture {
+            $(
+                #[doc = concat!("The `", $name, "` capture.")]
+                $variant
+            ),*
+        }
+
+        impl Capture {
+            /// Every capture, sorted by name.
+            pub const ALL: &'static [Capture] = &[$(Capture::$variant),*];
+
+            /// Returns the dotted name of the capture.
+            pub const fn name(self) -> &'static str {
+                match self {
+                    $(Capture::$variant => $name),*
+                }
+            }
+        }
+    };
+}
+
+// Expands (from build.rs) to an invocation with a variant for each of the
+// `STANDARD_CAPTURES`, in order.
+with_standard_captures!(define_standard_captures);
+
+impl Capture {
+    /// Returns the capture named `name`, if any.
+    pub fn from_name(name: &str) -> Option<Capture> {
+        STANDARD_CAPTURES.binary_search(&name).ok().map(|i| Capture::ALL[i])
+    }
+
+    /// Returns the closest ancestor of `self` in the taxonomy: the capture
+    /// named by the longest proper dotted prefix of `self`'s name, if any.
+    pub fn parent(self) -> Option<Capture> {
+        let mut name = self.name();
+        loop {
+            name = &name[..name.rfind('.')?];
+            if let Some(capture) = Capture::from_name(name) {
+                return Some(capture);
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Capture {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl AsRef<str> for Capture {
+    fn as_ref(&self) -> &str {
+        self.name()
+    }
+}
+
+impl From<Capture> for &'static str {
+    fn from(capture: Capture) -> Self {
+        capture.name()
+    }
+}
+
+impl From<Capture> for std::borrow::Cow<'static, str> {
+    fn from(capture: Capture) -> Self {
+        std::borrow::Cow::Borrowed(capture.name())
+    }
+}
+
+impl PartialEq<str> for Capture {
+    fn eq(&self, other: &str) -> bool {
+        self.name() == other
+    }
+}
+
+impl PartialEq<&str> for Capture {
+    fn eq(&self, other: &&str) -> bool {
+        self.name() == *other
+    }
+}
+
+impl PartialEq<Capture> for &str {
+    fn eq(&self, other: &Capture) -> bool {
+        *self == other.name()
+    }
+}
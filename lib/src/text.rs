@@ -0,0 +1,214 @@
+use std::sync::Arc;
+
+use crate::{Highlighter, Highlight, Theme, Error};
+use crate::span::{Line, Lines, Span, Spans};
+
+/// An owned, highlighted source.
+///
+/// Unlike the iterators returned by [`Highlighter`], a `HighlightedText` owns
+/// its source and highlights. It can be cached, sent across threads, rendered
+/// any number of times with any [`Theme`], and, with the `serde` feature,
+/// serialized. Cloning is cheap: the contents are reference counted.
+///
+/// # Example
+///
+/// ```rust
+/// use jellybean::{Language, HighlightedText, Theme, Capture, COMMON_CAPTURES};
+///
+/// let mut highlighter = Language::rust.custom_highlighter(COMMON_CAPTURES).unwrap();
+/// let text = HighlightedText::new(&mut highlighter, "fn main() {}").unwrap();
+///
+/// static THEME: Theme<&str> = Theme::new(&[
+///     (Capture::Function, "blue"),
+///     (Capture::Keyword, "red"),
+/// ]);
+/// let styled: Vec<_> = text.styled(&THEME).collect();
+/// assert_eq!(styled[0], ("fn", Some(&"red")));
+/// assert_eq!(styled[2], ("main", Some(&"blue")));
+///
+/// let copy = text.clone();
+/// std::thread::spawn(move || assert_eq!(copy.source(), "fn main() {}")).join().unwrap();
+/// ```
+#[derive(Clone)]
+pub struct HighlightedText(Arc<Inner>);
+
+struct Inner {
+    source: String,
+    captures: Vec<String>,
+    events: Vec<Event>,
+}
+
+/// A compact highlight event. Offsets and indices are stored as `u32`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Event {
+    /// Start of the highlight with the capture index.
+    Start(u32),
+    /// Source from the end of the previous `Source` to this offset.
+    Source(u32),
+    /// End of the innermost highlight.
+    End,
+}
+
+impl HighlightedText {
+    /// Highlights `source` with `highlighter`.
+    ///
+    /// Returns an error if highlighting fails or an
+    /// [`Error::SourceTooLarge`] if `source` is 4GiB or larger.
+    pub fn new(highlighter: &mut Highlighter, source: impl Into<String>) -> Result<Self, Error> {
+        let source = source.into();
+        if u32::try_from(source.len()).is_err() {
+            return Err(Error::SourceTooLarge { len: source.len(), max: u32::MAX as usize });
+        }
+
+        let captures = highlighter.captures().map(|s| s.to_owned()).collect();
+        let events = highlighter.highlight(&source)
+            .filter_map(|event| match event {
+                Ok(Highlight::Start { index, .. }) => Some(Ok(Event::Start(index as u32))),
+                Ok(Highlight::Source { start, end, .. }) if start == end => None,
+                Ok(Highlight::Source { end, .. }) => Some(Ok(Event::Source(end as u32))),
+                Ok(Highlight::End) => Some(Ok(Event::End)),
+                Err(e) => Some(Err(e)),
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(HighlightedText(Arc::new(Inner { source, captures, events })))
+    }
+
+    /// Returns the highlighted source.
+    pub fn source(&self) -> &str {
+        &self.0.source
+    }
+
+    /// Returns the names of the captures recognized when highlighting.
+    pub fn captures(&self) -> impl ExactSizeIterator<Item = &str> + '_ {
+        self.0.captures.iter().map(|s| s.as_str())
+    }
+
+    /// Returns an iterator over the highlight events, as emitted by
+    /// [`Highlighter::highlight()`] when the text was highlighted.
+    pub fn events(&self) -> impl Iterator<Item = Highlight<'_>> + '_ {
+        let Inner { source, captures, events } = &*self.0;
+        let mut offset = 0;
+        events.iter().map(move |event| match *event {
+            Event::Start(index) => Highlight::Start {
+                group: &captures[index as usize],
+                index: index as usize,
+            },
+            Event::Source(end) => {
+                let (start, end) = (offset, end as usize);
+                offset = end;
+                Highlight::Source { text: &source[start..end], start, end }
+            }
+            Event::End => Highlight::End,
+        })
+    }
+
+    /// Returns an iterator over the flattened spans of the text. See
+    /// [`Highlighter::highlight_spans()`].
+    pub fn spans(&self) -> impl Iterator<Item = Span<'_>> + '_ {
+        Spans::new(self.events().map(Ok)).flatten()
+    }
+
+    /// Returns an iterator over the lines of the text. See
+    /// [`Highlighter::highlight_lines()`].
+    pub fn lines(&self) -> impl Iterator<Item = Line<'_>> + '_ {
+        Lines::new(Spans::new(self.events().map(Ok))).flatten()
+    }
+
+    /// Returns an iterator over pieces of the source and their style in
+    /// `theme`, if any.
+    ///
+    /// The style of a piece is the style of its innermost highlight group
+    /// present in `theme`.
+    pub fn styled<'a, T>(
+        &'a self,
+        theme: &'a Theme<T>,
+    ) -> impl Iterator<Item = (&'a str, Option<&'a T>)> + 'a {
+        let theme = theme.compile_captures(self.captures());
+        self.spans().map(move |span| {
+            let style = span.groups.iter().rev().find_map(|group| theme.get(group.index));
+            (span.text, style)
+        })
+    }
+
+    /// Constructs `Self` from its parts, validating that the events are
+    /// consistent with `source` and `captures`.
+    #[cfg_attr(not(feature = "serde"), allow(dead_code))]
+    fn from_parts(
+        source: String,
+        captures: Vec<String>,
+        events: Vec<Event>,
+    ) -> Result<Self, &'static str> {
+        let (mut offset, mut depth) = (0, 0usize);
+        for event in &events {
+            match *event {
+                Event::Start(i) if i as usize >= captures.len() => return Err("unknown capture"),
+                Event::Start(_) => depth += 1,
+                Event::End => depth = depth.checked_sub(1).ok_or("unbalanced events")?,
+                Event::Source(end) => {
+                    let end = end as usize;
+                    if end < offset || !source.is_char_boundary(end) {
+                        return Err("invalid source offset");
+                    }
+
+                    offset = end;
+                }
+            }
+        }
+
+        if depth != 0 {
+            return Err("unbalanced events");
+        }
+
+        Ok(HighlightedText(Arc::new(Inner { source, captures, events })))
+    }
+}
+
+impl std::fmt::Debug for HighlightedText {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HighlightedText")
+            .field("source", &self.0.source)
+            .field("events", &self.0.events)
+            .finish()
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use serde::{Serialize, Serializer, Deserialize, Deserializer, de::Error};
+
+    use super::*;
+
+    /// `(kind, value)` pairs: `(0, index)`, `(1, end)`, or `(2, 0)`.
+    type SerializationData = (String, Vec<String>, Vec<(u8, u32)>);
+
+    impl Serialize for HighlightedText {
+        fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+            let events = self.0.events.iter()
+                .map(|event| match *event {
+                    Event::Start(index) => (0, index),
+                    Event::Source(end) => (1, end),
+                    Event::End => (2, 0),
+                })
+                .collect::<Vec<_>>();
+
+            (&self.0.source, &self.0.captures, events).serialize(ser)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for HighlightedText {
+        fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+            let (source, captures, events) = SerializationData::deserialize(de)?;
+            let events = events.into_iter()
+                .map(|(kind, value)| match kind {
+                    0 => Ok(Event::Start(value)),
+                    1 => Ok(Event::Source(value)),
+                    2 => Ok(Event::End),
+                    _ => Err(D::Error::custom("invalid event kind")),
+                })
+                .collect::<Result<_, _>>()?;
+
+            HighlightedText::from_parts(source, captures, events).map_err(D::Error::custom)
+        }
+    }
+}
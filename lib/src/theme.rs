@@ -1,21 +1,56 @@
 use std::borrow::Cow;
 use std::collections::BTreeMap;
 
+use crate::{Capture, Highlight, Highlighter};
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Theme<T: 'static> {
     Static(Set<T>),
+    Named(NamedSet<T>),
     Dynamic(Map<T>)
 }
 
-type Set<T> = &'static [(&'static str, T)];
+/// A [`Theme`] resolved against a list of captures.
+///
+/// Created by [`Theme::compile()`]. Finding the item for a highlight is a
+/// single index into the list of captures, as opposed to a search for each
+/// dotted prefix of the capture name with [`Theme::find()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompiledTheme<'a, T> {
+    /// The item for each capture, by capture index.
+    items: Vec<Option<&'a T>>,
+}
+
+type Set<T> = &'static [(Capture, T)];
+
+type NamedSet<T> = &'static [(&'static str, T)];
 
 type Map<T> = BTreeMap<Cow<'static, str>, T>;
 
 impl<T> Theme<T> {
-    pub const fn new(set: &'static [(&'static str, T)]) -> Self {
+    /// Creates a theme from a list of `(capture, item)` pairs sorted by
+    /// capture. Panics, at compile-time in a `const`, if the list isn't sorted
+    /// or contains duplicates.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jellybean::{Theme, Capture};
+    ///
+    /// static THEME: Theme<u8> = Theme::new(&[
+    ///     (Capture::Comment, 1),
+    ///     (Capture::Function, 2),
+    ///     (Capture::Keyword, 3),
+    /// ]);
+    ///
+    /// assert_eq!(THEME.find(Capture::FunctionMacro), Some(&2));
+    /// assert_eq!(THEME.find("keyword.return"), Some(&3));
+    /// assert_eq!(THEME.find_exact(Capture::FunctionMacro), None);
+    /// ```
+    pub const fn new(set: &'static [(Capture, T)]) -> Self {
         let mut i = 1;
         while i < set.len() {
-            let (a, b) = (set[i - 1].0, set[i].0);
+            let (a, b) = (set[i - 1].0.name(), set[i].0.name());
             if crate::util::const_compare(a.as_bytes(), b.as_bytes()).is_gt() {
                 panic!("theme set must be sorted by capture name");
             }
@@ -30,10 +65,44 @@ impl<T> Theme<T> {
         Theme::Static(set)
     }
 
-    pub fn find_exact(&self, capture: &str) -> Option<&T> {
+    /// Like [`Theme::new()`] but keyed by capture name, which isn't checked.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jellybean::Theme;
+    ///
+    /// static THEME: Theme<u8> = Theme::from_names(&[("function", 1), ("keyword", 2)]);
+    /// assert_eq!(THEME.find("function.macro"), Some(&1));
+    /// ```
+    pub const fn from_names(set: &'static [(&'static str, T)]) -> Self {
+        let mut i = 1;
+        while i < set.len() {
+            let (a, b) = (set[i - 1].0, set[i].0);
+            if crate::util::const_compare(a.as_bytes(), b.as_bytes()).is_gt() {
+                panic!("theme set must be sorted by capture name");
+            }
+
+            if crate::util::const_compare(a.as_bytes(), b.as_bytes()).is_eq() {
+                panic!("theme set cannot contain duplicate captures");
+            }
+
+            i += 1;
+        }
+
+        Theme::Named(set)
+    }
+
+    pub fn find_exact(&self, capture: impl AsRef<str>) -> Option<&T> {
+        let capture = capture.as_ref();
         match self {
             Theme::Dynamic(map) => map.get(capture),
             Theme::Static(list) => {
+                list.binary_search_by_key(&capture, |(c, _)| c.name()).ok()
+                    .and_then(|i| list.get(i))
+                    .map(|(_name, item)| item)
+            }
+            Theme::Named(list) => {
                 list.binary_search_by_key(&capture, |(name, _)| name).ok()
                     .and_then(|i| list.get(i))
                     .map(|(_name, item)| item)
@@ -41,7 +110,7 @@ impl<T> Theme<T> {
         }
     }
 
-    pub fn find(&self, capture: &str) -> Option<&T> {
+    pub fn find(&self, capture: impl AsRef<str>) -> Option<&T> {
         fn _find<'a, T, S, F>(capture: &str, set: &'a S, getter: F) -> Option<&'a T>
             where F: Fn(&'a S, &str) -> Option<&'a T>
         {
@@ -59,9 +128,15 @@ impl<T> Theme<T> {
             }
         }
 
+        let capture = capture.as_ref();
         match self {
             Theme::Dynamic(map) => _find(capture, map, |map, k| map.get(k)),
             Theme::Static(list) => _find(capture, list, |list, k| {
+                list.binary_search_by_key(&k, |(c, _)| c.name()).ok()
+                    .and_then(|i| list.get(i))
+                    .map(|(_name, item)| item)
+            }),
+            Theme::Named(list) => _find(capture, list, |list, k| {
                 list.binary_search_by_key(&k, |(name, _)| name).ok()
                     .and_then(|i| list.get(i))
                     .map(|(_name, item)| item)
@@ -70,6 +145,63 @@ impl<T> Theme<T> {
     }
 }
 
+impl<T> Theme<T> {
+    /// Resolves `self` against the captures recognized by `highlighter`.
+    ///
+    /// The returned theme is valid for highlights produced by `highlighter`
+    /// and by any highlighter recognizing the same captures.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jellybean::{Language, Highlight, Theme, Capture, COMMON_CAPTURES};
+    ///
+    /// static THEME: Theme<&str> = Theme::new(&[
+    ///     (Capture::Function, "blue"),
+    ///     (Capture::Keyword, "red"),
+    /// ]);
+    ///
+    /// let mut highlighter = Language::rust.custom_highlighter(COMMON_CAPTURES).unwrap();
+    /// let theme = THEME.compile(&highlighter);
+    /// for event in highlighter.highlight("fn main() {}") {
+    ///     if let Highlight::Start { group, index } = event.unwrap() {
+    ///         assert_eq!(theme.get(index), THEME.find(group));
+    ///     }
+    /// }
+    /// ```
+    pub fn compile(&self, highlighter: &Highlighter) -> CompiledTheme<'_, T> {
+        self.compile_captures(highlighter.captures())
+    }
+
+    /// Resolves `self` against the list of captures `captures`.
+    ///
+    /// Like [`Theme::compile()`] but for an arbitrary list of captures, e.g,
+    /// [`HighlightedText::captures()`](crate::HighlightedText::captures()).
+    pub fn compile_captures<I>(&self, captures: I) -> CompiledTheme<'_, T>
+        where I: IntoIterator, I::Item: AsRef<str>
+    {
+        let items = captures.into_iter().map(|capture| self.find(capture)).collect();
+        CompiledTheme { items }
+    }
+}
+
+impl<'a, T> CompiledTheme<'a, T> {
+    /// Returns the item for the capture with index `index`, if any.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&'a T> {
+        self.items.get(index).copied().flatten()
+    }
+
+    /// Returns the item for `highlight` if it's a [`Highlight::Start`].
+    #[inline]
+    pub fn find(&self, highlight: &Highlight<'_>) -> Option<&'a T> {
+        match highlight {
+            Highlight::Start { index, .. } => self.get(*index),
+            _ => None,
+        }
+    }
+}
+
 impl<S, T> FromIterator<(S, T)> for Theme<T>
     where S: Into<Cow<'static, str>>
 {
@@ -36,3 +36,22 @@ pub fn cmp_ignore_case_ascii(a: &str, b: &str) -> Ordering {
 
     a.len().cmp(&b.len())
 }
+
+/// Returns the byte range in `source` spanning the zero-indexed `lines`.
+///
+/// Each line includes its trailing newline, if any. Lines past the end of
+/// `source` are clamped to the end of `source`.
+pub fn line_byte_range(source: &str, lines: std::ops::Range<usize>) -> std::ops::Range<usize> {
+    let mut line_starts = std::iter::once(0).chain(source.bytes()
+        .enumerate()
+        .filter(|(_, b)| *b == b'\n')
+        .map(|(i, _)| i + 1));
+
+    let start = line_starts.nth(lines.start).unwrap_or(source.len());
+    let end = match lines.end.saturating_sub(lines.start) {
+        0 => start,
+        n => line_starts.nth(n - 1).unwrap_or(source.len()),
+    };
+
+    start..end
+}
@@ -0,0 +1,82 @@
+use jellybean::Language;
+
+fn detect(path: &str, content: &str) -> Option<&'static str> {
+    Language::detect(path, content).map(|language| language.name())
+}
+
+#[test]
+fn check_detect_precedence() {
+    // A modeline beats the file name, the shebang, and the extension.
+    let source = "#!/bin/sh\n# -*- mode: python -*-\nprint(1)\n";
+    assert_eq!(detect("Makefile.rs", source), Some("python"));
+
+    // A well-known name beats the shebang and extension.
+    assert_eq!(detect("Dockerfile.rs", "#!/usr/bin/env python3\n"), Some("dockerfile"));
+
+    // A shebang beats the extension.
+    assert_eq!(detect("serve.txt", "#!/usr/bin/env -S python3.11 -u\n"), Some("python"));
+    assert_eq!(detect("run.rs", "#!/bin/bash\n"), Some("bash"));
+    assert_eq!(detect("run.rs", "fn main() {}\n"), Some("rust"));
+}
+
+#[test]
+fn check_detect_names_and_extensions() {
+    assert_eq!(detect("GNUmakefile", ""), Some("make"));
+    assert_eq!(detect("/home/me/.zshrc", ""), Some("bash"));
+    assert_eq!(detect("views/index.html.erb", ""), Some("embedded_template"));
+    assert_eq!(detect("types/index.d.ts", ""), Some("typescript"));
+    assert_eq!(detect("NOTES", "vim: ft=markdown\n"), Some("markdown"));
+}
+
+#[test]
+fn check_ambiguous_file_types_are_ranked() {
+    let candidates = Language::candidates_for("include/vec.h");
+    assert!(candidates.len() > 1);
+    assert!(Language::candidates_for("index.d.ts").iter().any(|l| l.name() == "typescript"));
+    assert!(Language::candidates_for("").is_empty());
+
+    let source = "namespace v { template <typename T> class Vec { T* data; }; }\n";
+    assert_eq!(Language::disambiguate(&candidates, source)[0].name(), "cpp");
+    assert_eq!(detect("include/vec.h", source), Some("cpp"));
+
+    // Without content, ties keep the order of the candidates.
+    let first = candidates[0].name();
+    assert_eq!(Language::disambiguate(&candidates, "")[0].name(), first);
+}
+
+#[test]
+fn check_guesses_are_ranked_confidences() {
+    let top = |source: &str| Language::guess(source).first().map(|(l, _)| l.name());
+
+    assert_eq!(top("package main\n\nfunc main() {\n\tx := 1\n\tfmt.Println(x)\n}\n"), Some("go"));
+    assert_eq!(top("SELECT id, name FROM users WHERE id = 1;\n"), Some("sql"));
+    assert_eq!(top("{\"name\": \"jellybean\", \"tags\": [\"a\", \"b\"]}\n"), Some("json"));
+    assert_eq!(top("#!/bin/sh\nls\n"), Some("bash"));
+    assert_eq!(top("  \n"), None);
+    assert_eq!(top("qqq zzz\n"), None);
+
+    let guesses = Language::guess("def f(self):\n    return None\n");
+    let total: f32 = guesses.iter().map(|(_, confidence)| confidence).sum();
+    assert!((total - 1.0).abs() < 1e-4);
+    assert!(guesses.windows(2).all(|w| w[0].1 >= w[1].1));
+}
+
+#[test]
+fn check_well_known_names_dont_shadow_extensions() {
+    assert_eq!(detect("build.rs", ""), Some("rust"));
+    assert_eq!(detect("build.py", ""), Some("python"));
+    assert_ne!(detect("build.sh", ""), Some("starlark"));
+    assert_ne!(detect("build.gradle", ""), Some("starlark"));
+    assert_ne!(detect("workspace.json", ""), Some("starlark"));
+    assert_ne!(detect("Gemfile.lock", ""), Some("ruby"));
+    assert_ne!(detect("makefile.rs", ""), Some("make"));
+
+    assert_eq!(detect("BUILD", ""), Some("starlark"));
+    assert_eq!(detect("makefile", ""), Some("make"));
+    assert_eq!(detect("Makefile.am", ""), Some("make"));
+    assert_eq!(detect("Containerfile.prod", ""), Some("dockerfile"));
+
+    // No CMake grammar is bundled, so `CMakeLists.txt` isn't detected.
+    assert!(Language::find_by_file_type("txt").is_none());
+    assert_eq!(detect("CMakeLists.txt", ""), None);
+}
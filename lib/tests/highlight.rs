@@ -0,0 +1,249 @@
+use std::sync::Arc;
+
+use jellybean::{Language, Highlight, Injection, Error, COMMON_CAPTURES, EXHAUSTIVE_CAPTURES, STANDARD_CAPTURES};
+
+const SOURCE: &str = r#"/// Docs.
+fn main() {
+    let s = "a
+multi-line string";
+    println!("{s}");
+}
+"#;
+
+#[test]
+fn check_lines_cover_source() {
+    let mut hl = Language::rust.custom_highlighter(COMMON_CAPTURES).unwrap();
+    let lines = hl.highlight_lines(SOURCE).collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(lines.len(), SOURCE.lines().count());
+
+    for (line, expected) in lines.iter().zip(SOURCE.lines()) {
+        let text: String = line.spans.iter().map(|span| span.text).collect();
+        assert_eq!(text, expected);
+        assert_eq!(&SOURCE[line.range.clone()], expected);
+    }
+
+    let string_line = &lines[3];
+    assert_eq!(string_line.spans[0].group().unwrap().name, "string");
+}
+
+#[test]
+fn check_lines_trim_crlf_in_tokens() {
+    // Rust line comments extend to the `\n`, so the `\r` ends the comment.
+    let source = "// a\r\nfn main() {}\r\n";
+    let mut hl = Language::rust.custom_highlighter(COMMON_CAPTURES).unwrap();
+    let lines = hl.highlight_lines(source).collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(lines.len(), 2);
+
+    for (line, expected) in lines.iter().zip(source.lines()) {
+        let text: String = line.spans.iter().map(|span| span.text).collect();
+        assert_eq!(text, expected);
+        assert_eq!(&source[line.range.clone()], expected);
+    }
+
+    let comment = lines[0].spans.last().unwrap();
+    assert_eq!(comment.text, "// a");
+    assert_eq!(comment.group().unwrap().name, "comment");
+}
+
+#[test]
+fn check_span_groups_are_nested_outermost_first() {
+    let source = r#"println!("a\n");"#;
+    let mut hl = Language::rust.custom_highlighter(COMMON_CAPTURES).unwrap();
+    let spans = hl.highlight_spans(source).collect::<Result<Vec<_>, _>>().unwrap();
+    let names = |text: &str| spans.iter()
+        .find(|span| span.text == text)
+        .map(|span| span.groups.iter().map(|g| g.name).collect::<Vec<_>>())
+        .unwrap();
+
+    assert_eq!(names("println"), ["function.macro"]);
+    assert_eq!(names("\\n"), ["string", "escape"]);
+
+    // The groups of each span are the stack of `Start` events open over it.
+    let (mut stack, mut stacks) = (vec![], vec![]);
+    for event in hl.highlight(source) {
+        match event.unwrap() {
+            Highlight::Start { group, .. } => stack.push(group.to_string()),
+            Highlight::End => { stack.pop(); }
+            Highlight::Source { start, end, .. } => stacks.push((start..end, stack.clone())),
+        }
+    }
+
+    for span in &spans {
+        let (_, stack) = stacks.iter().find(|(r, _)| r.contains(&span.range.start)).unwrap();
+        let groups = span.groups.iter().map(|g| g.name.to_string()).collect::<Vec<_>>();
+        assert_eq!(&groups, stack, "{:?}", span.text);
+    }
+}
+
+#[test]
+fn check_range_events_are_balanced() {
+    let mut hl = Language::rust.custom_highlighter(COMMON_CAPTURES).unwrap();
+    for start in 0..SOURCE.len() {
+        let (mut depth, mut text) = (0isize, String::new());
+        for event in hl.highlight_range(SOURCE, start..SOURCE.len().min(start + 7)) {
+            match event.unwrap() {
+                Highlight::Start { .. } => depth += 1,
+                Highlight::End => depth -= 1,
+                Highlight::Source { text: t, .. } => text.push_str(t),
+            }
+
+            assert!(depth >= 0);
+        }
+
+        assert_eq!(depth, 0);
+        assert_eq!(text, &SOURCE[start..SOURCE.len().min(start + 7)]);
+    }
+}
+
+#[test]
+fn check_parse_errors() {
+    assert!(Language::rust.parse_errors(SOURCE).unwrap().is_empty());
+
+    let broken = SOURCE.replace("let s =", "let s = =");
+    let errors = Language::rust.parse_errors(&broken).unwrap();
+    assert!(!errors.is_empty());
+    assert_eq!(errors[0].start.row, 2);
+}
+
+#[test]
+fn check_captures_are_normalized() {
+    for capture in EXHAUSTIVE_CAPTURES {
+        let standard = jellybean::normalize_capture(capture).unwrap();
+        assert!(STANDARD_CAPTURES.contains(&standard), "{capture} -> {standard}");
+    }
+
+    let mut hl = Language::rust.custom_highlighter(&EXHAUSTIVE_CAPTURES[..]).unwrap();
+    for event in hl.highlight(SOURCE) {
+        if let Highlight::Start { group, .. } = event.unwrap() {
+            assert!(STANDARD_CAPTURES.contains(&group), "{group} is not standard");
+        }
+    }
+}
+
+#[test]
+fn check_normalization_keeps_pattern_captures_distinct() {
+    // `@identifier` and `@var.reference` both normalize to `variable`.
+    let highlighter = |overrides: &[(&str, &str)]| Language::rust.config_builder()
+        .highlights(r#"(let_declaration
+            pattern: (identifier) @identifier
+            value: (identifier) @var.reference
+            (#not-eq? @identifier @var.reference))"#)
+        .capture_overrides(overrides.iter().copied())
+        .highlighter(&["variable", "keyword"])
+        .unwrap();
+
+    let starts = |hl: &mut jellybean::Highlighter, source| hl.highlight(source)
+        .filter_map(|event| match event.unwrap() {
+            Highlight::Start { group, .. } => Some(group.to_string()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let mut hl = highlighter(&[]);
+    assert_eq!(starts(&mut hl, "fn f() { let a = b; }"), ["variable", "variable"]);
+    assert!(starts(&mut hl, "fn f() { let a = a; }").is_empty());
+
+    let mut hl = highlighter(&[("var", "keyword")]);
+    assert_eq!(starts(&mut hl, "fn f() { let a = b; }"), ["variable", "keyword"]);
+}
+
+#[test]
+fn check_query_errors_are_positioned_in_the_original_query() {
+    // `@var.reference` normalizes to the shorter `@variable`.
+    let result = Language::rust.config_builder()
+        .highlights("(identifier) @var.reference\n(identifier) @var.reference (oops)")
+        .highlighter(COMMON_CAPTURES);
+
+    match result {
+        Err(Error::Query { query, row, column, .. }) => {
+            assert_eq!((query, row, column), ("highlights", 1, 29));
+        }
+        _ => panic!("expected a query error"),
+    }
+}
+
+#[test]
+fn check_appended_query_errors_are_positioned_in_the_appended_text() {
+    let result = Language::rust.config_builder()
+        .append_highlights("(identifier) @variable\n  (oops)")
+        .highlighter(COMMON_CAPTURES);
+
+    match result {
+        Err(Error::Query { query, row, column, .. }) => {
+            assert_eq!((query, row, column), ("highlights", 1, 3));
+        }
+        _ => panic!("expected a query error"),
+    }
+}
+
+#[test]
+fn check_registered_languages_are_injected() {
+    let host = Language::register(
+        "registry-host",
+        &["registry-host"],
+        jellybean::raw::rust::language,
+        &[
+            ("highlights", "(identifier) @variable"),
+            ("injections", r#"((token_tree) @injection.content (#set! injection.language "registry-guest"))"#),
+        ],
+    ).unwrap();
+
+    Language::register(
+        "registry-guest",
+        &[],
+        jellybean::raw::rust::language,
+        &[("highlights", "(identifier) @constant")],
+    ).unwrap();
+
+    assert_eq!(Language::find("REGISTRY-HOST").unwrap().name(), "registry-host");
+    assert!(Language::find_by_file_type("registry-guest").is_none());
+
+    let mut hl = host.custom_highlighter(&STANDARD_CAPTURES[..]).unwrap();
+    let groups = hl.highlight("m!{ let y = 1; }")
+        .filter_map(|event| match event.unwrap() {
+            Highlight::Start { group, .. } => Some(group),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    assert!(groups.contains(&"variable"));
+    assert!(groups.contains(&"constant"));
+}
+
+#[test]
+fn check_foreign_injection_captures_are_errors() {
+    let mut hl = Language::markdown.custom_highlighter(&["comment"]).unwrap();
+    let config = Arc::new(Language::rust.highlight_config(COMMON_CAPTURES).unwrap());
+    hl.set_injector(move |_| Some(Injection::Config(config.clone())));
+
+    let source = "```rust\nfn main() { let x = 1; }\n```\n";
+    let result = hl.highlight(source).collect::<Result<Vec<_>, _>>();
+    assert!(matches!(result, Err(Error::UnknownCapture { .. })));
+}
+
+#[test]
+fn check_broken_injection_configs_are_errors() {
+    let host = Language::register(
+        "broken-injection-host",
+        &[],
+        jellybean::raw::rust::language,
+        &[
+            ("highlights", "(identifier) @variable"),
+            ("injections", r#"((token_tree) @injection.content (#set! injection.language "broken-injection-guest"))"#),
+        ],
+    ).unwrap();
+
+    Language::register(
+        "broken-injection-guest",
+        &[],
+        jellybean::raw::rust::language,
+        &[("highlights", "(not_a_node) @constant")],
+    ).unwrap();
+
+    let mut hl = host.custom_highlighter(COMMON_CAPTURES).unwrap();
+    let result = hl.highlight("m!{ let y = 1; }").collect::<Result<Vec<_>, _>>();
+    match result {
+        Err(Error::InvalidInjection { name, .. }) => assert_eq!(name, "broken-injection-guest"),
+        result => panic!("expected an invalid injection: {result:?}"),
+    }
+}
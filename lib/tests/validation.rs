@@ -11,7 +11,107 @@ fn check_api_compat() {
 #[test]
 fn check_hl_creation() {
     for language in ALL_LANGUAGES {
-        let hl = language.custom_highlighter(EXHAUSTIVE_CAPTURES);
+        let hl = language.custom_highlighter(EXHAUSTIVE_CAPTURES).unwrap();
         assert_eq!(hl.language().name(), language.name())
     }
 }
+
+#[test]
+fn check_tags_creation() {
+    for language in ALL_LANGUAGES {
+        if language.find_query("tags").is_some() {
+            let tagger = language.tagger();
+            assert!(tagger.is_ok(), "{}: {}", language.name(), tagger.err().unwrap());
+        }
+    }
+}
+
+#[test]
+fn check_folds_creation() {
+    for language in ALL_LANGUAGES {
+        // Folding falls back to brackets, so check the query compiles itself.
+        if let Some(query) = language.find_query("folds") {
+            let result = tree_sitter::Query::new(language.raw(), query);
+            assert!(result.is_ok(), "{}: {:?}", language.name(), result.err().unwrap());
+        }
+
+        assert!(language.folding_ranges("").unwrap().is_empty());
+    }
+}
+
+#[test]
+fn check_capture_taxonomy() {
+    use jellybean::{Capture, STANDARD_CAPTURES};
+
+    let names = Capture::ALL.iter().map(|c| c.name()).collect::<Vec<_>>();
+    assert_eq!(names, STANDARD_CAPTURES);
+    assert!(STANDARD_CAPTURES.windows(2).all(|w| w[0] < w[1]));
+    assert!(Capture::ALL.windows(2).all(|w| w[0] < w[1]));
+    for capture in Capture::ALL {
+        assert_eq!(Capture::from_name(capture.name()), Some(*capture));
+    }
+}
+
+#[test]
+fn check_aliases_are_unambiguous() {
+    for language in ALL_LANGUAGES {
+        for alias in language.aliases() {
+            assert!(jellybean::Language::find_by_name(alias).is_none(), "{alias} is a name");
+            assert_eq!(jellybean::Language::find_by_alias(alias).unwrap().name(), language.name());
+
+            // File types take precedence over aliases.
+            let found = jellybean::Language::find(alias).unwrap();
+            match jellybean::Language::find_by_file_type(alias) {
+                Some(by_file_type) => assert_eq!(found.name(), by_file_type.name()),
+                None => assert_eq!(found.name(), language.name()),
+            }
+        }
+    }
+}
+
+#[test]
+fn check_metadata() {
+    use jellybean::Language;
+
+    for language in ALL_LANGUAGES {
+        let name = language.name();
+        assert!(!language.display_name().is_empty(), "{name}: no display name");
+        assert!(language.abi_version() >= tree_sitter::MIN_COMPATIBLE_LANGUAGE_VERSION);
+        assert!(language.abi_version() <= tree_sitter::LANGUAGE_VERSION);
+        assert!(language.repository().is_some(), "{name}: no repository");
+        assert!(language.revision().is_some(), "{name}: no revision");
+        assert!(language.description().map_or(true, |d| !d.is_empty()));
+    }
+
+    assert_eq!(Language::c_sharp.display_name(), "C#");
+    assert_eq!(Language::markdown_inline.display_name(), "Markdown (Inline)");
+    assert_eq!(Language::rust.display_name(), "Rust");
+    assert_eq!(Language::rust.license(), Some("MIT"));
+    assert_eq!(Language::python.license(), Some("MIT"));
+    assert_eq!(Language::rust.repository(), Some("https://github.com/tree-sitter/tree-sitter-rust"));
+    assert_eq!(Language::rust.revision().map(str::len), Some(40));
+}
+
+#[test]
+fn check_editor_metadata() {
+    for language in ALL_LANGUAGES {
+        let indent = language.indent();
+        assert!(indent == "\t" || (!indent.is_empty() && indent.bytes().all(|b| b == b' ')));
+        assert!(language.line_comments().iter().all(|marker| !marker.is_empty()));
+        assert!(language.brackets().iter().all(|(open, close)| !open.is_empty() && !close.is_empty()));
+        if let Some((open, close)) = language.block_comment() {
+            assert!(!open.is_empty() && !close.is_empty());
+        }
+    }
+
+    assert_eq!(jellybean::Language::erlang.line_comments(), ["%"]);
+    assert_eq!(jellybean::Language::pod.brackets(), [("<", ">")]);
+}
+
+#[test]
+#[cfg(feature = "dynamic")]
+fn check_duplicate_loads_are_rejected_early() {
+    // The library doesn't exist: a duplicate must be rejected before loading.
+    let result = unsafe { jellybean::Loader::new("Rust", "/nonexistent/rust.so").load() };
+    assert!(matches!(result, Err(jellybean::Error::DuplicateLanguage { .. })));
+}
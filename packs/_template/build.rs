@@ -5,9 +5,36 @@ use std::path::{PathBuf, Path};
 use rayon::prelude::*;
 use tinyjson::JsonValue;
 
-const HIGHLIGHT_QUERIES: &[&str] = &["locals", "highlights", "injections"];
+const BUNDLED_QUERIES: &[&str] = &["locals", "highlights", "injections", "tags", "folds"];
 const LANGUAGE_PACK: &str = "pack.tar.zst";
 const LANGUAGE_DIR: &str = "languages";
+const SOURCE_INFO: &str = "jellybean-source.json";
+const EDITOR_INFO: &str = "jellybean-editor.json";
+
+/// Display names that aren't the title-cased language name.
+const DISPLAY_NAMES: &[(&str, &str)] = &[
+    ("bibtex", "BibTeX"), ("bitbake", "BitBake"), ("c_sharp", "C#"), ("capnp", "Cap'n Proto"),
+    ("cpon", "CPON"), ("cpp", "C++"), ("css", "CSS"), ("csv", "CSV"), ("cuda", "CUDA"),
+    ("cue", "CUE"), ("dot", "DOT"), ("dtd", "DTD"), ("eex", "EEx"), ("elisp", "Emacs Lisp"),
+    ("firrtl", "FIRRTL"), ("fsh", "FSH"), ("func", "FunC"), ("gitattributes", "Git Attributes"),
+    ("gitcommit", "Git Commit"), ("glsl", "GLSL"), ("gomod", "Go Module"),
+    ("gosum", "Go Checksums"), ("gowork", "Go Workspace"), ("heex", "HEEx"), ("hjson", "Hjson"),
+    ("hocon", "HOCON"), ("html", "HTML"), ("htmldjango", "HTML (Django)"), ("http", "HTTP"),
+    ("ispc", "ISPC"), ("janet_simple", "Janet"), ("javascript", "JavaScript"),
+    ("jsdoc", "JSDoc"), ("json", "JSON"), ("kdl", "KDL"), ("lalrpop", "LALRPOP"),
+    ("llvm", "LLVM IR"), ("llvm_mir", "LLVM MIR"), ("luadoc", "LuaDoc"),
+    ("luap", "Lua Patterns"), ("m68k", "M68k Assembly"), ("markdown_inline", "Markdown (Inline)"),
+    ("objc", "Objective-C"), ("ocaml", "OCaml"), ("ocamllex", "OCamllex"), ("pgn", "PGN"),
+    ("php", "PHP"), ("po", "Gettext PO"), ("pod", "POD"), ("proto", "Protocol Buffers"),
+    ("prql", "PRQL"), ("psv", "PSV"), ("ql", "CodeQL"), ("qmldir", "QML Directory"),
+    ("qmljs", "QML"), ("re2c", "re2c"), ("ron", "RON"), ("scss", "SCSS"), ("sql", "SQL"),
+    ("sql_bigquery", "SQL (BigQuery)"), ("sqlite", "SQLite"),
+    ("ssh_client_config", "SSH Config"), ("supercollider", "SuperCollider"),
+    ("systemtap", "SystemTap"), ("t32", "T32"), ("tablegen", "TableGen"), ("tlaplus", "TLA+"),
+    ("toml", "TOML"), ("tsv", "TSV"), ("tsx", "TSX"), ("typescript", "TypeScript"),
+    ("usd", "USD"), ("vhs", "VHS"), ("vim", "Vim Script"), ("wgsl", "WGSL"), ("xml", "XML"),
+    ("yang", "YANG"),
+];
 
 type JsonMap = std::collections::HashMap<String, JsonValue>;
 type JsonArray = Vec<JsonValue>;
@@ -25,6 +52,68 @@ struct TsMetadata {
     file_types: Vec<String>,
     queries: Vec<(String, PathBuf)>,
     description: String,
+    display_name: String,
+    license: String,
+    repository: String,
+    revision: String,
+    editor: EditorInfo,
+}
+
+/// Editor metadata, as written by `xtask fetch` from `xtask/editor.toml`.
+#[derive(Debug)]
+struct EditorInfo {
+    line_comments: Vec<String>,
+    block_comment: Option<(String, String)>,
+    brackets: Vec<(String, String)>,
+    indent: String,
+}
+
+/// No comments or brackets and four-space indentation, as for languages
+/// created at runtime. Used for packs generated before editor metadata was.
+impl Default for EditorInfo {
+    fn default() -> Self {
+        EditorInfo {
+            line_comments: vec![],
+            block_comment: None,
+            brackets: vec![],
+            indent: "    ".into(),
+        }
+    }
+}
+
+impl EditorInfo {
+    fn read(path: &Path) -> Option<EditorInfo> {
+        let value: JsonValue = std::fs::read_to_string(path).ok()?.parse().ok()?;
+        let json = value.get::<JsonMap>()?;
+        let strings = |value: &JsonValue| -> Option<Vec<String>> {
+            value.get::<JsonArray>()?.iter().map(|v| v.get::<String>().cloned()).collect()
+        };
+
+        let pair = |value: &JsonValue| match strings(value)?.as_slice() {
+            [open, close] => Some((open.clone(), close.clone())),
+            _ => None,
+        };
+
+        let indent = match json.get("indent")? {
+            JsonValue::Number(width) => " ".repeat(*width as usize),
+            JsonValue::String(tab) if tab == "tab" => "\t".into(),
+            _ => return None,
+        };
+
+        Some(EditorInfo {
+            line_comments: strings(json.get("line-comments")?)?,
+            block_comment: match json.get("block-comment")? {
+                JsonValue::Null => None,
+                value => Some(pair(value)?),
+            },
+            brackets: json.get("brackets")?
+                .get::<JsonArray>()?
+                .iter()
+                .map(pair)
+                .collect::<Option<_>>()?,
+            indent,
+        })
+    }
 }
 
 impl TsMetadata {
@@ -52,7 +141,7 @@ impl TsMetadata {
             .find(|p| p.exists())
             .expect(&format!("{raw_name}: missing src ({root_candidates:?})"));
 
-        let (description, ts_json) = root_candidates.iter()
+        let (description, license, ts_json) = root_candidates.iter()
             .map(|root| root.join("package.json"))
             .filter_map(|path| Self::parse_package_json(&raw_name, &path))
             .next()
@@ -78,7 +167,7 @@ impl TsMetadata {
             .map(|root| root.join("queries"))
             .find_map(|path| Self::discover_queries(&path))
             .unwrap_or_else(|| {
-                HIGHLIGHT_QUERIES.iter()
+                BUNDLED_QUERIES.iter()
                     .filter_map(|query| ts_json.get(*query).map(|value| (query, value)))
                     .filter_map(|(name, v)| {
                         let value = v.get::<JsonArray>()?.first()?.get::<String>()?;
@@ -88,10 +177,45 @@ impl TsMetadata {
                     .collect()
             });
 
-        TsMetadata { raw_name, rust_name, enabled, src_dir, file_types, queries, description, }
+        let (repository, revision) = Self::parse_source_info(&path.join(SOURCE_INFO))
+            .unwrap_or_default();
+
+        let editor = EditorInfo::read(&path.join(EDITOR_INFO)).unwrap_or_default();
+        let display_name = Self::display_name(&rust_name);
+        TsMetadata {
+            raw_name, rust_name, enabled, src_dir, file_types, queries, description,
+            display_name, license, repository, revision, editor,
+        }
     }
 
-    fn parse_package_json(name: &str, path: &Path) -> Option<(String, JsonMap)> {
+    fn display_name(rust_name: &str) -> String {
+        if let Some((_, name)) = DISPLAY_NAMES.iter().find(|(k, _)| *k == rust_name) {
+            return name.to_string();
+        }
+
+        rust_name.split(|c| c == '_' || c == '-')
+            .map(|word| {
+                let mut chars = word.chars();
+                chars.next()
+                    .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Reads the `(repository, revision)` recorded by `xtask fetch`.
+    fn parse_source_info(path: &Path) -> Option<(String, String)> {
+        let value: JsonValue = std::fs::read_to_string(path).ok()?.parse().ok()?;
+        let field = |key: &str| value.get::<JsonMap>()?
+            .get(key)?
+            .get::<String>()
+            .map(|s| s.to_string());
+
+        Some((field("repository")?, field("revision")?))
+    }
+
+    fn parse_package_json(name: &str, path: &Path) -> Option<(String, String, JsonMap)> {
         let reader = io::BufReader::new(File::open(path).ok()?);
         let value: JsonValue = io::read_to_string(reader).ok()?.parse().ok()?;
         let mut json: JsonMap = value.try_into().ok()?;
@@ -103,6 +227,15 @@ impl TsMetadata {
             .map(|s| s.to_string())
             .unwrap_or_default();
 
+        let license = match json.get("license") {
+            Some(JsonValue::String(license)) => license.clone(),
+            Some(JsonValue::Object(license)) => license.get("type")
+                .and_then(|s| s.get::<String>())
+                .cloned()
+                .unwrap_or_default(),
+            _ => String::new(),
+        };
+
         let map = match json.get_mut("tree-sitter").map(take_json_value) {
             Some(JsonValue::Object(map)) => Some(map),
             Some(JsonValue::Array(mut array)) => {
@@ -123,7 +256,7 @@ impl TsMetadata {
             _ => None
         };
 
-        Some((description, map.unwrap_or_default()))
+        Some((description, license, map.unwrap_or_default()))
     }
 
     fn discover_queries(path: &Path) -> Option<Vec<(String, PathBuf)>> {
@@ -131,7 +264,7 @@ impl TsMetadata {
         let queries = entries.filter_map(|entry| entry.ok().map(|e| e.path()))
             .filter(|path| path.extension().map_or(false, |e| e == "scm"))
             .map(|path| (path.file_stem().unwrap().to_string_lossy().into_owned(), path))
-            .filter(|(name, _)| HIGHLIGHT_QUERIES.contains(&&**name))
+            .filter(|(name, _)| BUNDLED_QUERIES.contains(&&**name))
             .collect();
 
         Some(queries)
@@ -160,7 +293,12 @@ impl TsMetadata {
     }
 
     fn write_module_line(&self, sink: &mut dyn io::Write) -> io::Result<()> {
-        let TsMetadata { rust_name, file_types, queries, description, .. } = self;
+        let TsMetadata {
+            rust_name, file_types, queries, description, display_name, license, repository,
+            revision, editor, ..
+        } = self;
+
+        let EditorInfo { line_comments, block_comment, brackets, indent } = editor;
 
         let query_keys = queries.iter().map(|k| &k.0).collect::<Vec<_>>();
         let expanded_queries: Vec<_> = queries.iter()
@@ -176,13 +314,39 @@ impl TsMetadata {
                 /// A description of the tree-sitter language. May be empty.
                 pub const DESCRIPTION: &'static str = {description:?};
 
+                /// The human-friendly name of the language: `{display_name:?}`.
+                pub const DISPLAY_NAME: &'static str = {display_name:?};
+
+                /// The grammar's license, as an SPDX expression, from its
+                /// `package.json`. May be empty.
+                pub const LICENSE: &'static str = {license:?};
+
+                /// The URL of the upstream grammar repository. May be empty.
+                pub const REPOSITORY: &'static str = {repository:?};
+
+                /// The git revision of the grammar in this pack. May be empty.
+                pub const REVISION: &'static str = {revision:?};
+
+                /// The line comment markers, the preferred marker first.
+                pub const LINE_COMMENTS: &'static [&'static str] = &{line_comments:?};
+
+                /// The `(open, close)` block comment markers, if any.
+                pub const BLOCK_COMMENT: Option<(&'static str, &'static str)> = {block_comment:?};
+
+                /// The `(open, close)` bracket pairs.
+                pub const BRACKETS: &'static [(&'static str, &'static str)] = &{brackets:?};
+
+                /// One level of indentation: a tab or a number of spaces.
+                pub const INDENT: &'static str = {indent:?};
+
                 /// The file types reported as supported by the language.
                 ///
                 /// This is a slice of file types (i.e, file extensions) that
                 /// identify this language.
                 pub const FILE_TYPES: &'static [&'static str] = &{file_types:?};
 
-                /// The highlighting queries.
+                /// The bundled queries: highlighting queries and, if the
+                /// grammar provides them, `tags` and `folds` queries.
                 ///
                 /// The slice contains (key, value) pairs where the key is the
                 /// name of the query and the value is the query itself. This
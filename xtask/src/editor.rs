@@ -0,0 +1,165 @@
+use std::io;
+use std::path::Path;
+use std::collections::BTreeSet;
+use std::sync::OnceLock;
+
+use toml_edit::{Document, Item};
+
+use crate::crate_path;
+
+/// Editor metadata for a language, as declared in `editor.toml`.
+#[derive(Debug)]
+pub struct EditorInfo {
+    pub line_comments: Vec<String>,
+    pub block_comment: Option<(String, String)>,
+    pub brackets: Vec<(String, String)>,
+    /// The indentation width in spaces or `None` for tabs.
+    pub indent: Option<usize>,
+}
+
+impl EditorInfo {
+    pub fn source_file() -> &'static Path {
+        crate_path!("editor.toml")
+    }
+
+    fn document() -> &'static Document {
+        static DOCUMENT: OnceLock<Document> = OnceLock::new();
+        DOCUMENT.get_or_init(|| {
+            std::fs::read_to_string(Self::source_file())
+                .expect("failed to read editor.toml")
+                .parse()
+                .expect("editor.toml is invalid TOML")
+        })
+    }
+
+    /// Returns the editor metadata for the language `name`, filling in any
+    /// missing keys from `[default]`.
+    pub fn get(name: &str) -> io::Result<EditorInfo> {
+        let document = Self::document();
+        let language = document.get("languages").and_then(|languages| languages.get(name));
+        let default = document.get("default");
+        let key = |key: &str| language.and_then(|l| l.get(key)).or_else(|| default?.get(key));
+
+        let invalid = |key: &str| io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("editor.toml: invalid `{key}` for `{name}`"),
+        );
+
+        let strings = |item: &Item| -> Option<Vec<String>> {
+            item.as_array()?.iter().map(|v| v.as_str().map(String::from)).collect()
+        };
+
+        let line_comments = match key("line-comments") {
+            Some(item) => strings(item).ok_or_else(|| invalid("line-comments"))?,
+            None => vec![],
+        };
+
+        let block_comment = match key("block-comment").map(strings) {
+            Some(Some(pair)) if pair.len() == 2 => Some((pair[0].clone(), pair[1].clone())),
+            Some(_) => return Err(invalid("block-comment")),
+            None => None,
+        };
+
+        let brackets = key("brackets")
+            .and_then(|item| item.as_array())
+            .ok_or_else(|| invalid("brackets"))?
+            .iter()
+            .map(|v| {
+                let pair = v.as_array()
+                    .map(|a| a.iter().filter_map(|v| v.as_str()).collect::<Vec<_>>());
+
+                match pair.as_deref() {
+                    Some([open, close]) => Ok((open.to_string(), close.to_string())),
+                    _ => Err(invalid("brackets")),
+                }
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+
+        let indent = match key("indent") {
+            Some(item) if item.as_str() == Some("tab") => None,
+            Some(item) => match item.as_integer() {
+                Some(width) if width > 0 => Some(width as usize),
+                _ => return Err(invalid("indent")),
+            },
+            None => return Err(invalid("indent")),
+        };
+
+        Ok(EditorInfo { line_comments, block_comment, brackets, indent })
+    }
+
+    /// Checks that `editor.toml` has a table for exactly the languages named
+    /// in `names`, so that no language silently takes only the defaults.
+    pub fn check_tables<'a>(names: impl IntoIterator<Item = &'a str>) -> io::Result<()> {
+        let tables = Self::document().get("languages")
+            .and_then(|languages| languages.as_table_like())
+            .map(|languages| languages.iter().map(|(name, _)| name).collect::<BTreeSet<_>>())
+            .unwrap_or_default();
+
+        let names = names.into_iter().collect::<BTreeSet<_>>();
+        let list = |names: Vec<&&str>| names.iter()
+            .map(|name| format!("`{name}`"))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let missing = names.difference(&tables).collect::<Vec<_>>();
+        if !missing.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("editor.toml: missing tables for {}", list(missing)),
+            ));
+        }
+
+        let unknown = tables.difference(&names).collect::<Vec<_>>();
+        if !unknown.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("editor.toml: tables for unknown languages {}", list(unknown)),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Serializes `self` as the JSON read by the pack build script.
+    pub fn to_json(&self) -> String {
+        let line_comments = self.line_comments.iter()
+            .map(|comment| format!("{comment:?}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let block_comment = match &self.block_comment {
+            Some((open, close)) => format!("[{open:?}, {close:?}]"),
+            None => "null".into(),
+        };
+
+        let brackets = self.brackets.iter()
+            .map(|(open, close)| format!("[{open:?}, {close:?}]"))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let indent = match self.indent {
+            Some(width) => width.to_string(),
+            None => "\"tab\"".into(),
+        };
+
+        format!("{{\n  \"line-comments\": [{line_comments}],\n  \
+            \"block-comment\": {block_comment},\n  \
+            \"brackets\": [{brackets}],\n  \
+            \"indent\": {indent}\n}}\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EditorInfo;
+    use crate::fetch::TsLanguage;
+
+    #[test]
+    fn check_every_language_has_editor_info() {
+        let names = TsLanguage::iter().map(|lang| lang.name).collect::<Vec<_>>();
+        EditorInfo::check_tables(names.iter().copied()).unwrap();
+        for name in names {
+            EditorInfo::get(name).unwrap();
+        }
+    }
+}
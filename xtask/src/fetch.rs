@@ -5,6 +5,7 @@ use std::sync::OnceLock;
 
 use indicatif::{ProgressBar, ProgressStyle};
 
+use crate::editor::EditorInfo;
 use crate::util::{Semaphore, visible, flag, verbose};
 use crate::{crate_path, cmd, vprintln};
 
@@ -30,6 +31,18 @@ impl TsLanguage {
         Self::checkout_container().join(self.name)
     }
 
+    /// The file, packaged with the language, recording where its source was
+    /// fetched from. Read by the pack build script.
+    pub fn source_info_path(&self) -> PathBuf {
+        self.checkout_path().join("jellybean-source.json")
+    }
+
+    /// The file, packaged with the language, holding its editor metadata from
+    /// `editor.toml`. Read by the pack build script.
+    pub fn editor_info_path(&self) -> PathBuf {
+        self.checkout_path().join("jellybean-editor.json")
+    }
+
     pub fn iter() -> impl Iterator<Item = TsLanguage> {
         static LANGUAGE_SOURCE: OnceLock<String> = OnceLock::new();
 
@@ -89,6 +102,9 @@ impl TsLanguage {
             }
         }
 
+        self.write_source_info()?;
+        write_if_changed(&self.editor_info_path(), &EditorInfo::get(self.name)?.to_json())?;
+
         // Remove any `Cargo.toml` so `cargo publish` doesn't ignore the dir.
         let walker = walkdir::WalkDir::new(&lang_dir).max_depth(3).into_iter();
         for entry in walker.filter_entry(visible) {
@@ -102,9 +118,35 @@ impl TsLanguage {
         Ok(())
     }
 
+    /// Records the repository and checked out revision of `self` in
+    /// `self.source_info_path()`.
+    fn write_source_info(&self) -> io::Result<()> {
+        let output = std::process::Command::new("git")
+            .current_dir(self.checkout_path())
+            .args(["rev-parse", "HEAD"])
+            .output()?;
+
+        if !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr);
+            return Err(io::Error::other(error.trim().to_string()));
+        }
+
+        let revision = String::from_utf8_lossy(&output.stdout);
+        let json = format!(
+            "{{\n  \"repository\": {:?},\n  \"revision\": {:?}\n}}\n",
+            self.git_url,
+            revision.trim(),
+        );
+
+        write_if_changed(&self.source_info_path(), &json)
+    }
+
     pub fn fetch_and_sync_all(update: bool) -> io::Result<()> {
-        // Remove any language not in the source file.
+        // Check the editor metadata covers every language before fetching.
         let declared_languages = TsLanguage::iter().collect::<Vec<_>>();
+        EditorInfo::check_tables(declared_languages.iter().map(|lang| lang.name))?;
+
+        // Remove any language not in the source file.
         if Self::checkout_container().exists() {
             let declared_language_paths = declared_languages.iter()
                 .map(|lang| lang.checkout_path())
@@ -143,6 +185,16 @@ impl TsLanguage {
     }
 }
 
+/// Writes `contents` to `path` unless it already contains exactly that, so
+/// that unchanged metadata doesn't make packs appear outdated.
+fn write_if_changed(path: &Path, contents: &str) -> io::Result<()> {
+    if fs::read_to_string(path).is_ok_and(|existing| existing == contents) {
+        return Ok(());
+    }
+
+    fs::write(path, contents)
+}
+
 pub fn main(args: &[&str]) -> io::Result<()> {
     let update = flag(args, "u");
     println!(":: fetching languages (updating? {update})");
@@ -4,6 +4,7 @@ mod fetch;
 mod package;
 mod expand;
 mod sync;
+mod editor;
 
 pub const USAGE: &str = r"
 usage:
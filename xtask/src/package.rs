@@ -29,12 +29,16 @@ impl PackBuilder {
     const INCLUDE: &'static [&'static str] = &[
         "**/*.h",
         "**/package.json",
+        "**/jellybean-source.json",
+        "**/jellybean-editor.json",
         "**/src/parser.c",
         "**/src/scanner.c",
         "**/src/scanner.cc",
         "**/queries/locals.scm",
         "**/queries/highlights.scm",
         "**/queries/injections.scm",
+        "**/queries/tags.scm",
+        "**/queries/folds.scm",
     ];
 
     const EXCLUDE: &'static [&'static str] = &[